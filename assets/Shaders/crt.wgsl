@@ -0,0 +1,38 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+@group(0) @binding(1) var texture_sampler: sampler;
+
+struct CrtSettings {
+    scanlines: f32,
+    distortion: f32,
+    aberration: f32,
+}
+@group(0) @binding(2) var<uniform> settings: CrtSettings;
+
+const PI: f32 = 3.14159265;
+// Screen pixels per scanline.
+const SCANLINE_PERIOD: f32 = 3.0;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    // Barrel distortion. Push pixels out further the further they are from the center.
+    let centered = in.uv * 2.0 - 1.0;
+    let warped = centered * (1.0 + settings.distortion * dot(centered, centered));
+    let uv = warped * 0.5 + 0.5;
+
+    // Split red and blue away from the center.
+    let offset = centered * settings.aberration;
+    let center = textureSample(screen_texture, texture_sampler, uv);
+    let red = textureSample(screen_texture, texture_sampler, uv + offset).r;
+    let blue = textureSample(screen_texture, texture_sampler, uv - offset).b;
+    var color = vec3(red, center.g, blue);
+
+    let height = f32(textureDimensions(screen_texture).y);
+    let line = 0.5 + 0.5 * sin(uv.y * height * 2.0 * PI / SCANLINE_PERIOD);
+    color *= 1.0 - settings.scanlines * line;
+
+    // Black border where the curved screen pulls away from the edges.
+    let inside = all(uv >= vec2(0.0)) && all(uv <= vec2(1.0));
+    return vec4(select(vec3(0.0), color, inside), center.a);
+}
@@ -0,0 +1,16 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+struct StarfieldMaterial {
+    offset: vec2<f32>,
+    repeat: vec2<f32>,
+};
+
+@group(1) @binding(0) var<uniform> material: StarfieldMaterial;
+@group(1) @binding(1) var texture: texture_2d<f32>;
+@group(1) @binding(2) var texture_sampler: sampler;
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    // Tile the texture across the quad. The sampler repeats so scrolling wraps seamlessly.
+    return textureSample(texture, texture_sampler, mesh.uv * material.repeat + material.offset);
+}
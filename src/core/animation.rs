@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use bevy::{asset::LoadedFolder, prelude::*};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+const ANIMATIONS_DIR: &str = "embedded://Animations";
+/// Gap between frames in an atlas so filtering doesn't bleed into neighbors.
+const ATLAS_PADDING: UVec2 = UVec2::splat(2);
+
+/// Animations with a folder of numbered frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+pub enum AnimationId {
+    Player,
+    Bullet,
+    LogoAsteroids,
+    LogoCredits,
+}
+
+impl AnimationId {
+    fn folder(&self) -> String {
+        let name = match self {
+            AnimationId::Player => "obj_player",
+            AnimationId::Bullet => "obj_bullet",
+            AnimationId::LogoAsteroids => "Logo_asteroids",
+            AnimationId::LogoCredits => "Logo_credits",
+        };
+        format!("{ANIMATIONS_DIR}/{name}/Default")
+    }
+}
+
+/// Atlas of an animation and the atlas index of each frame in playback order.
+pub struct SpriteFrames {
+    pub atlas: Handle<TextureAtlas>,
+    frames: Vec<usize>,
+}
+
+/// Frame folders being loaded and the animations built from them.
+#[derive(Resource, Default)]
+pub struct SpriteAnimations {
+    folders: HashMap<AnimationId, Handle<LoadedFolder>>,
+    loaded: HashMap<AnimationId, SpriteFrames>,
+}
+
+impl SpriteAnimations {
+    /// Atlas for an animation. Empty until its frames load.
+    pub fn atlas(&self, id: AnimationId) -> Handle<TextureAtlas> {
+        self.loaded
+            .get(&id)
+            .map(|frames| frames.atlas.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LoopMode {
+    #[default]
+    Loop,
+    /// Stop on the last frame.
+    Once,
+    /// Play forwards then backwards.
+    PingPong,
+}
+
+/// Plays an animation on a sprite sheet or UI atlas image.
+#[derive(Component)]
+pub struct SpriteAnimation {
+    pub id: AnimationId,
+    pub mode: LoopMode,
+    frame: usize,
+    /// Playing backwards in [`LoopMode::PingPong`].
+    reversed: bool,
+    timer: Timer,
+}
+
+impl SpriteAnimation {
+    pub fn new(id: AnimationId, fps: f32, mode: LoopMode) -> Self {
+        Self {
+            id,
+            mode,
+            frame: 0,
+            reversed: false,
+            timer: Timer::from_seconds(1.0 / fps, TimerMode::Repeating),
+        }
+    }
+
+    /// Move to the next frame of an animation with `len` frames.
+    fn advance(&mut self, len: usize) {
+        let last = len.saturating_sub(1);
+        match self.mode {
+            LoopMode::Loop => self.frame = (self.frame + 1) % len.max(1),
+            LoopMode::Once => self.frame = (self.frame + 1).min(last),
+            LoopMode::PingPong => {
+                if self.frame == last && !self.reversed {
+                    self.reversed = true;
+                } else if self.frame == 0 && self.reversed {
+                    self.reversed = false;
+                }
+                self.frame = if self.reversed {
+                    self.frame.saturating_sub(1)
+                } else {
+                    (self.frame + 1).min(last)
+                };
+            }
+        }
+    }
+}
+
+pub fn setup_animations(asset_server: Res<AssetServer>, mut animations: ResMut<SpriteAnimations>) {
+    animations.folders = AnimationId::iter()
+        .map(|id| (id, asset_server.load_folder(id.folder())))
+        .collect();
+}
+
+/// Pack each frame folder into a texture atlas once all its frames load.
+pub fn build_animation_atlases(
+    mut folder_events: EventReader<AssetEvent<LoadedFolder>>,
+    loaded_folders: Res<Assets<LoadedFolder>>,
+    mut images: ResMut<Assets<Image>>,
+    mut atlases: ResMut<Assets<TextureAtlas>>,
+    mut animations: ResMut<SpriteAnimations>,
+) {
+    for event in folder_events.read() {
+        let AssetEvent::LoadedWithDependencies { id: folder_id } = event else {
+            continue;
+        };
+        let (Some(id), Some(folder)) = (
+            animations
+                .folders
+                .iter()
+                .find_map(|(id, folder)| (folder.id() == *folder_id).then_some(*id)),
+            loaded_folders.get(*folder_id),
+        ) else {
+            continue;
+        };
+
+        // Frames are numbered so play them in path order.
+        let mut frame_handles: Vec<Handle<Image>> = folder
+            .handles
+            .iter()
+            .map(|handle| handle.clone().typed::<Image>())
+            .collect();
+        frame_handles.sort_by_key(|handle| handle.path().map(|path| path.to_string()));
+
+        let mut builder = TextureAtlasBuilder::default().padding(ATLAS_PADDING);
+        for handle in &frame_handles {
+            if let Some(image) = images.get(handle) {
+                builder.add_texture(handle.id(), image);
+            }
+        }
+        let atlas = match builder.finish(&mut images) {
+            Ok(atlas) => atlas,
+            Err(err) => {
+                error!("Failed to build atlas for {id:?}: {err:?}");
+                continue;
+            }
+        };
+        let frames = frame_handles
+            .iter()
+            .filter_map(|handle| atlas.get_texture_index(handle))
+            .collect();
+        animations.loaded.insert(
+            id,
+            SpriteFrames {
+                atlas: atlases.add(atlas),
+                frames,
+            },
+        );
+    }
+}
+
+/// Give animations spawned before their frames loaded an atlas.
+pub fn attach_animation_atlases(
+    animations: Res<SpriteAnimations>,
+    mut atlas_query: Query<(&SpriteAnimation, &mut Handle<TextureAtlas>)>,
+) {
+    for (animation, mut atlas) in &mut atlas_query {
+        if atlas.id() == AssetId::default() {
+            *atlas = animations.atlas(animation.id);
+        }
+    }
+}
+
+/// Step animations and show their current frame.
+pub fn animate_sprites(
+    time: Res<Time>,
+    animations: Res<SpriteAnimations>,
+    mut animation_query: Query<(
+        &mut SpriteAnimation,
+        Option<&mut TextureAtlasSprite>,
+        Option<&mut UiTextureAtlasImage>,
+    )>,
+) {
+    for (mut animation, sprite, ui_image) in &mut animation_query {
+        let Some(frames) = animations.loaded.get(&animation.id) else {
+            continue;
+        };
+        let steps = animation
+            .timer
+            .tick(time.delta())
+            .times_finished_this_tick();
+        for _ in 0..steps {
+            animation.advance(frames.frames.len());
+        }
+
+        let Some(index) = frames.frames.get(animation.frame).copied() else {
+            continue;
+        };
+        if let Some(mut sprite) = sprite {
+            if sprite.index != index {
+                sprite.index = index;
+            }
+        }
+        if let Some(mut ui_image) = ui_image {
+            if ui_image.index != index {
+                ui_image.index = index;
+            }
+        }
+    }
+}
@@ -1,22 +1,35 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, f32::consts::TAU};
 
-use bevy::prelude::*;
-use strum::{EnumCount, IntoEnumIterator};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use rand::Rng;
+use serde::Deserialize;
+use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
 use super::{
-    position::{Position, BOUNDS},
+    audio::{PlaySound, SoundEvent},
+    player::Player,
+    position::{Playfield, Position},
+    settings::Settings,
+    vector::Outline,
     velocity::Velocity,
 };
-use crate::GetRandom;
 
-const ASTEROID_NUM: usize = 12;
-pub const ASTEROID_SPLIT_NUM: usize = 2;
+pub const ASTEROID_NUM: usize = 12;
 pub const ASTEROID_VELOCITY: f32 = 1.0;
+/// Extra asteroids each wave, up to double the difficulty's count.
+const ASTEROIDS_PER_WAVE: usize = 2;
+/// Closest a new wave's asteroid spawns to the ship.
+const ASTEROID_SAFE_DISTANCE: f32 = 150.0;
+const ASTEROID_SPAWN_ATTEMPTS: usize = 10;
 
 const ASTEROID_IMG_DIR: &str = "embedded://Animations/obj_asteroid/Default";
+const ASTEROID_DEFS: &str = include_str!("../../assets/Data/asteroids.ron");
+const ASTEROID_MATERIALS: &str = include_str!("../../assets/Data/materials.ron");
+/// Allowed difference between a collider and its texture's size, as a fraction of the texture's.
+const ASTEROID_RADIUS_TOLERANCE: f32 = 0.2;
 
-#[derive(Debug, EnumIter, EnumCount, Default, PartialEq, Eq, Hash, Clone, Copy)]
+#[derive(Debug, EnumIter, Default, PartialEq, Eq, Hash, Clone, Copy, Deserialize)]
 pub enum AsteroidSize {
     #[default]
     Large,
@@ -25,84 +38,377 @@ pub enum AsteroidSize {
     Tiny,
 }
 
+#[derive(Debug, EnumIter, Default, PartialEq, Eq, Hash, Clone, Copy, Deserialize)]
+pub enum AsteroidMaterial {
+    #[default]
+    Rock,
+    Armored,
+    Explosive,
+    Icy,
+    Magnetic,
+}
+
 #[derive(Default, Component, Clone)]
 pub struct Asteroid {
     /// Asteroid size.
     pub size: AsteroidSize,
+    pub material: AsteroidMaterial,
 }
 
-impl AsteroidSize {
-    pub fn scale(&self) -> f32 {
-        match self {
-            AsteroidSize::Large => 55.0,
-            AsteroidSize::Medium => 35.0,
-            AsteroidSize::Small => 15.0,
-            AsteroidSize::Tiny => 7.5,
+/// Hits left before an asteroid is destroyed.
+#[derive(Component, Deref, DerefMut)]
+pub struct Health(pub u32);
+
+/// Current wave. Clearing the field starts the next.
+#[derive(Debug, Resource, Default, Deref)]
+pub struct Wave(pub u32);
+
+/// Fragments spawned when an asteroid is destroyed.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct AsteroidChildren {
+    pub size: AsteroidSize,
+    pub count: usize,
+}
+
+/// Asteroid type for a size. Loaded from `assets/Data/asteroids.ron`.
+#[derive(Debug, Deserialize)]
+pub struct AsteroidDef {
+    /// Frame in the asteroid animation folder.
+    pub texture: String,
+    /// Collision radius.
+    pub radius: f32,
+    pub score: usize,
+    pub children: Option<AsteroidChildren>,
+    /// Min and max speed before difficulty scaling.
+    pub speed: (f32, f32),
+    #[serde(skip)]
+    pub image: Handle<Image>,
+}
+
+#[derive(Debug, Resource, Deref)]
+pub struct AsteroidDefs(HashMap<AsteroidSize, AsteroidDef>);
+
+impl AsteroidDefs {
+    /// Check every size is defined and splitting always ends.
+    fn validate(&self) -> Result<(), String> {
+        for size in AsteroidSize::iter() {
+            let def = self
+                .get(&size)
+                .ok_or_else(|| format!("{size:?} is not defined"))?;
+            if def.radius <= 0.0 {
+                return Err(format!("{size:?} radius must be positive"));
+            }
+            if def.speed.0 < 0.0 || def.speed.0 > def.speed.1 {
+                return Err(format!("{size:?} speed must be a range of positive speeds"));
+            }
+            // Children must be smaller so splitting can't loop.
+            if let Some(children) = def.children {
+                let child_radius = self.get(&children.size).map_or(0.0, |child| child.radius);
+                if child_radius >= def.radius {
+                    return Err(format!("{size:?} splits into larger {:?}", children.size));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check a size's collider matches its texture of `texture_size`, measured by average half extent.
+    fn fits_texture(&self, size: AsteroidSize, texture_size: Vec2) -> Result<(), String> {
+        let def = &self[&size];
+        let texture_radius = (texture_size.x + texture_size.y) / 4.0;
+        if (def.radius - texture_radius).abs() > texture_radius * ASTEROID_RADIUS_TOLERANCE {
+            return Err(format!(
+                "{size:?} asteroid radius {} doesn't match its {} texture of radius {texture_radius}",
+                def.radius, def.texture
+            ));
         }
+        Ok(())
     }
+
+    /// Number of hits to fully clear an asteroid with `health` left and its fragments,
+    /// which share its material.
+    pub fn hits_to_clear(&self, size: AsteroidSize, material: &MaterialDef, health: u32) -> u32 {
+        health
+            + self[&size].children.map_or(0, |children| {
+                (children.count + material.extra_children) as u32
+                    * self.hits_to_clear(children.size, material, material.hp.max(1))
+            })
+    }
+}
+
+/// Behavior of an asteroid material. Loaded from `assets/Data/materials.ron`.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct MaterialDef {
+    pub tint: (f32, f32, f32),
+    /// Added to the size's score.
+    pub score: usize,
+    /// Spawn weight on the first wave the material appears.
+    pub weight: f32,
+    pub weight_per_wave: f32,
+    pub first_wave: u32,
+    pub hp: u32,
+    /// Neighbors within this distance are hit when destroyed.
+    pub blast_radius: f32,
+    /// Fragments spawned on top of the size's children.
+    pub extra_children: usize,
+    /// Acceleration towards the ship.
+    pub pull: f32,
 }
 
-impl From<AsteroidSize> for usize {
-    fn from(value: AsteroidSize) -> Self {
-        match value {
-            AsteroidSize::Large => 3,
-            AsteroidSize::Medium => 2,
-            AsteroidSize::Small => 1,
-            AsteroidSize::Tiny => 0,
+impl Default for MaterialDef {
+    fn default() -> Self {
+        Self {
+            tint: (1.0, 1.0, 1.0),
+            score: 0,
+            weight: 0.0,
+            weight_per_wave: 0.0,
+            first_wave: 1,
+            hp: 1,
+            blast_radius: 0.0,
+            extra_children: 0,
+            pull: 0.0,
         }
     }
 }
 
-impl From<usize> for AsteroidSize {
-    fn from(value: usize) -> Self {
-        match value.clamp(0, AsteroidSize::COUNT - 1) {
-            3 => AsteroidSize::Large,
-            2 => AsteroidSize::Medium,
-            1 => AsteroidSize::Small,
-            0 => AsteroidSize::Tiny,
-            _ => unreachable!(),
+impl MaterialDef {
+    pub fn color(&self) -> Color {
+        let (r, g, b) = self.tint;
+        Color::rgb(r, g, b)
+    }
+
+    fn spawn_weight(&self, wave: u32) -> f32 {
+        if wave < self.first_wave {
+            return 0.0;
         }
+        (self.weight + self.weight_per_wave * (wave - self.first_wave) as f32).max(0.0)
     }
 }
 
 #[derive(Debug, Resource, Deref)]
-pub struct AsteroidImages(HashMap<AsteroidSize, Handle<Image>>);
-
-pub fn setup_asteroids(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let asteroid_images = AsteroidImages(
-        AsteroidSize::iter()
-            .enumerate()
-            .map(|(i, size)| {
-                (
-                    size,
-                    asset_server.load(format!("{ASTEROID_IMG_DIR}/00{i}.png")),
-                )
-            })
-            .collect(),
-    );
-    for _ in 0..ASTEROID_NUM {
-        let mut pos = Position::random();
-        // Within bounds of window.
-        pos.x *= BOUNDS.x;
-        pos.y *= BOUNDS.y;
+pub struct AsteroidMaterials(HashMap<AsteroidMaterial, MaterialDef>);
+
+impl AsteroidMaterials {
+    /// Pick a material by spawn weight for a wave.
+    fn pick(&self, wave: u32, rng: &mut impl Rng) -> AsteroidMaterial {
+        let total: f32 = self.values().map(|def| def.spawn_weight(wave)).sum();
+        let mut roll = rng.gen_range(0.0..total.max(f32::EPSILON));
+        for material in AsteroidMaterial::iter() {
+            let weight = self
+                .get(&material)
+                .map_or(0.0, |def| def.spawn_weight(wave));
+            if roll < weight {
+                return material;
+            }
+            roll -= weight;
+        }
+        AsteroidMaterial::default()
+    }
+}
+
+pub fn setup_asteroid_defs(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let mut defs: HashMap<AsteroidSize, AsteroidDef> =
+        ron::from_str(ASTEROID_DEFS).expect("Asteroid definitions should be valid RON");
+    for def in defs.values_mut() {
+        def.image = asset_server.load(format!("{ASTEROID_IMG_DIR}/{}", def.texture));
+    }
+
+    let defs = AsteroidDefs(defs);
+    if let Err(err) = defs.validate() {
+        panic!("Invalid asteroid definitions: {err}");
+    }
+    commands.insert_resource(defs);
+
+    let materials: HashMap<AsteroidMaterial, MaterialDef> =
+        ron::from_str(ASTEROID_MATERIALS).expect("Asteroid materials should be valid RON");
+    if let Some(material) =
+        AsteroidMaterial::iter().find(|material| !materials.contains_key(material))
+    {
+        panic!("Invalid asteroid materials: {material:?} is not defined");
+    }
+    commands.insert_resource(AsteroidMaterials(materials));
+}
 
-        let asteroid_size = AsteroidSize::Large;
-        let asteroid_img_handle = asteroid_images[&asteroid_size].clone();
+/// Report asteroid textures whose size doesn't match their collider.
+pub fn validate_asteroid_textures(
+    mut image_events: EventReader<AssetEvent<Image>>,
+    images: Res<Assets<Image>>,
+    defs: Res<AsteroidDefs>,
+) {
+    for event in image_events.read() {
+        let AssetEvent::LoadedWithDependencies { id } = event else {
+            continue;
+        };
+        let Some((size, _)) = defs.iter().find(|(_, def)| def.image.id() == *id) else {
+            continue;
+        };
+        let Some(image) = images.get(*id) else {
+            continue;
+        };
+        if let Err(err) = defs.fits_texture(*size, image.size_f32()) {
+            error!("{err}");
+        }
+    }
+}
+
+/// Everything needed to spawn asteroids.
+#[derive(SystemParam)]
+pub struct AsteroidSpawner<'w> {
+    pub defs: Res<'w, AsteroidDefs>,
+    pub materials: Res<'w, AsteroidMaterials>,
+    pub settings: Res<'w, Settings>,
+    pub playfield: Res<'w, Playfield>,
+}
+
+impl AsteroidSpawner<'_> {
+    /// Spawn an asteroid moving in a random direction.
+    pub fn spawn(&self, commands: &mut Commands, asteroid: Asteroid, pos: Position) {
+        let def = &self.defs[&asteroid.size];
+        let material = &self.materials[&asteroid.material];
+        let mut rng = rand::thread_rng();
+        let speed =
+            rng.gen_range(def.speed.0..=def.speed.1) * self.settings.difficulty.asteroid_velocity();
 
         commands.spawn((
-            Asteroid {
-                size: asteroid_size,
-            },
+            asteroid,
+            Health(material.hp.max(1)),
             SpriteBundle {
-                texture: asteroid_img_handle,
+                sprite: Sprite {
+                    color: material.color(),
+                    ..default()
+                },
+                texture: def.image.clone(),
                 // Translation of 1.0 keeps asteroid over ship.
                 transform: Transform::default().with_translation(Vec3::new(pos.x, pos.y, 1.0)),
                 ..default()
             },
-            Velocity(Velocity::random().normalize() * ASTEROID_VELOCITY),
+            Velocity(Vec2::from_angle(rng.gen_range(0.0..TAU)) * speed),
+            Outline::jagged(def.radius, material.color(), &mut rng),
             pos,
         ));
     }
-    // Insert asteroid images as a resource to access later.
-    commands.insert_resource(asteroid_images);
+
+    /// Spawn a wave of large asteroids away from the ship.
+    pub fn spawn_wave(&self, commands: &mut Commands, wave: u32, ship_pos: Vec2) {
+        let base_num = self.settings.difficulty.asteroid_num();
+        let num =
+            (base_num + ASTEROIDS_PER_WAVE * wave.saturating_sub(1) as usize).min(base_num * 2);
+        let mut rng = rand::thread_rng();
+        for _ in 0..num {
+            // Within bounds of window. Give up on keeping clear of the ship after a few tries.
+            let mut pos = self.playfield.random_position();
+            for _ in 0..ASTEROID_SPAWN_ATTEMPTS {
+                if pos.distance(ship_pos) >= ASTEROID_SAFE_DISTANCE {
+                    break;
+                }
+                pos = self.playfield.random_position();
+            }
+
+            let asteroid = Asteroid {
+                size: AsteroidSize::Large,
+                material: self.materials.pick(wave, &mut rng),
+            };
+            self.spawn(commands, asteroid, Position(pos));
+        }
+    }
+}
+
+pub fn setup_asteroids(mut commands: Commands, spawner: AsteroidSpawner) {
+    commands.insert_resource(Wave(1));
+    // Ship starts at the center.
+    spawner.spawn_wave(&mut commands, 1, Vec2::ZERO);
+}
+
+/// Start the next wave once the field is cleared. Clearing the field no longer ends the game.
+pub fn check_wave_cleared(
+    mut commands: Commands,
+    spawner: AsteroidSpawner,
+    mut wave: ResMut<Wave>,
+    asteroid_query: Query<(), With<Asteroid>>,
+    ship_query: Query<&Position, With<Player>>,
+    mut sound_events: EventWriter<PlaySound>,
+) {
+    if !asteroid_query.is_empty() {
+        return;
+    }
+    sound_events.send(PlaySound::new(SoundEvent::Victory));
+    wave.0 += 1;
+    let ship_pos = ship_query.get_single().map_or(Vec2::ZERO, |pos| pos.0);
+    spawner.spawn_wave(&mut commands, wave.0, ship_pos);
+}
+
+/// Pull magnetic asteroids towards the ship, up to their size's max speed.
+pub fn attract_magnetic_asteroids(
+    defs: Res<AsteroidDefs>,
+    materials: Res<AsteroidMaterials>,
+    settings: Res<Settings>,
+    playfield: Res<Playfield>,
+    ship_query: Query<&Position, With<Player>>,
+    mut asteroid_query: Query<(&Asteroid, &Position, &mut Velocity)>,
+) {
+    let Ok(ship_pos) = ship_query.get_single() else {
+        return;
+    };
+    for (asteroid, pos, mut velocity) in &mut asteroid_query {
+        let pull = materials[&asteroid.material].pull;
+        if pull == 0.0 {
+            continue;
+        }
+        let max_speed = defs[&asteroid.size].speed.1 * settings.difficulty.asteroid_velocity();
+        // Pull the short way around so the ship can't hide across an edge.
+        let direction = playfield
+            .wrapped_delta(ship_pos.0 - pos.0)
+            .normalize_or_zero();
+        velocity.0 = (velocity.0 + direction * pull).clamp_length_max(max_speed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_defs() -> AsteroidDefs {
+        AsteroidDefs(
+            ron::from_str(ASTEROID_DEFS).expect("Asteroid definitions should be valid RON"),
+        )
+    }
+
+    #[test]
+    fn asteroid_defs_are_valid() {
+        load_defs().validate().unwrap();
+    }
+
+    #[test]
+    fn asteroid_colliders_fit_textures() {
+        let defs = load_defs();
+        for size in AsteroidSize::iter() {
+            let path = format!(
+                "{}/assets/Animations/obj_asteroid/Default/{}",
+                env!("CARGO_MANIFEST_DIR"),
+                defs[&size].texture
+            );
+            let (width, height) = image::image_dimensions(&path)
+                .unwrap_or_else(|err| panic!("Can't read {path}: {err}"));
+            defs.fits_texture(size, Vec2::new(width as f32, height as f32))
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn hits_to_clear_counts_health_and_extra_fragments() {
+        let defs = load_defs();
+        let plain = MaterialDef::default();
+        assert_eq!(defs.hits_to_clear(AsteroidSize::Tiny, &plain, 1), 1);
+        assert_eq!(defs.hits_to_clear(AsteroidSize::Large, &plain, 1), 15);
+
+        let armored = MaterialDef {
+            hp: 2,
+            extra_children: 1,
+            ..default()
+        };
+        // Damaged once, with three fragments of two hits each per split.
+        assert_eq!(defs.hits_to_clear(AsteroidSize::Small, &armored, 1), 7);
+        assert_eq!(defs.hits_to_clear(AsteroidSize::Large, &armored, 1), 79);
+    }
 }
@@ -0,0 +1,63 @@
+use bevy::{ecs::system::SystemParam, input::mouse::MouseMotion, prelude::*};
+
+use super::{AppState, NextStates};
+use crate::ui::menu::MenuState;
+
+/// Seconds without input on the main menu before the demo starts.
+const ATTRACT_IDLE_SECS: f32 = 20.0;
+
+/// Time the main menu has been left alone.
+#[derive(Resource)]
+pub struct IdleTimer(Timer);
+
+impl Default for IdleTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(ATTRACT_IDLE_SECS, TimerMode::Once))
+    }
+}
+
+/// Keyboard, mouse and gamepad buttons.
+#[derive(SystemParam)]
+pub struct ButtonInputs<'w> {
+    keyboard: Res<'w, Input<KeyCode>>,
+    mouse: Res<'w, Input<MouseButton>>,
+    gamepad: Res<'w, Input<GamepadButton>>,
+}
+
+impl ButtonInputs<'_> {
+    /// Any key, mouse button or gamepad button pressed this frame.
+    fn any_just_pressed(&self) -> bool {
+        self.keyboard.get_just_pressed().next().is_some()
+            || self.mouse.get_just_pressed().next().is_some()
+            || self.gamepad.get_just_pressed().next().is_some()
+    }
+}
+
+pub fn reset_idle_timer(mut idle_timer: ResMut<IdleTimer>) {
+    idle_timer.0.reset();
+}
+
+/// Start the demo once the main menu is idle long enough.
+pub fn start_attract_mode(
+    time: Res<Time>,
+    buttons: ButtonInputs,
+    mut mouse_motion: EventReader<MouseMotion>,
+    mut idle_timer: ResMut<IdleTimer>,
+    mut next_states: NextStates,
+) {
+    let moved = mouse_motion.read().count() > 0;
+    if moved || buttons.any_just_pressed() {
+        idle_timer.0.reset();
+        return;
+    }
+    if idle_timer.0.tick(time.delta()).just_finished() {
+        next_states.set(AppState::Attract, MenuState::Disabled);
+    }
+}
+
+/// Return to the main menu on any press.
+pub fn exit_attract_mode(buttons: ButtonInputs, mut next_states: NextStates) {
+    if buttons.any_just_pressed() {
+        next_states.set(AppState::Menu, MenuState::Main);
+    }
+}
@@ -1,47 +1,318 @@
-use bevy::{audio::PlaybackMode, prelude::*};
+use std::collections::{HashMap, VecDeque};
 
-#[derive(Resource)]
-pub struct BulletFiredAudio(pub Handle<AudioSource>);
+use bevy::{audio::Volume, ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
 
-#[derive(Resource)]
-pub struct AsteroidDestroyedAudio(pub Handle<AudioSource>);
+use super::{
+    controller::ShipControls,
+    player::Player,
+    position::{Playfield, Position},
+    settings::Settings,
+    AppState,
+};
 
-#[derive(Resource)]
-pub struct ShipDestroyedAudio(pub Handle<AudioSource>);
+/// Max number of the same sound playing at once. The oldest is stopped to play another.
+const MAX_SOUND_INSTANCES: usize = 3;
+/// Music volume multiplier while paused.
+const MUSIC_DUCK_VOLUME: f32 = 0.3;
+/// Volume of positioned sounds at the far side of the screen from the ship.
+const MIN_ATTENUATED_VOLUME: f32 = 0.4;
+/// Seconds for a [`Fade`] to go from silent to full volume.
+pub const FADE_SECS: f32 = 1.5;
 
-#[derive(Resource)]
-pub struct VictoryAudio(pub Handle<AudioSource>);
+/// Gameplay events with a sound attached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+pub enum SoundEvent {
+    BulletFired,
+    AsteroidDamaged,
+    AsteroidDestroyed,
+    AsteroidExploded,
+    ShipDestroyed,
+    Victory,
+    Loss,
+    Thrust,
+    UiBlip,
+}
+
+impl SoundEvent {
+    fn path(&self) -> &'static str {
+        match self {
+            SoundEvent::BulletFired => "embedded://Files/shoot.ogg",
+            SoundEvent::AsteroidDamaged => "embedded://Files/blip.ogg",
+            SoundEvent::AsteroidDestroyed => "embedded://Files/hurt.ogg",
+            SoundEvent::AsteroidExploded => "embedded://Files/boom.ogg",
+            SoundEvent::ShipDestroyed => "embedded://Files/boom.ogg",
+            SoundEvent::Victory => "embedded://Files/win.ogg",
+            SoundEvent::Loss => "embedded://Files/lose.ogg",
+            SoundEvent::Thrust => "embedded://Files/fire.ogg",
+            SoundEvent::UiBlip => "embedded://Files/blip.ogg",
+        }
+    }
+
+    fn channel(&self) -> AudioChannel {
+        match self {
+            SoundEvent::UiBlip => AudioChannel::Ui,
+            _ => AudioChannel::Sfx,
+        }
+    }
+}
+
+/// Loaded sound for each [`SoundEvent`].
+#[derive(Resource, Deref)]
+pub struct Sounds(HashMap<SoundEvent, Handle<AudioSource>>);
 
-#[derive(Resource)]
-pub struct LossAudio(pub Handle<AudioSource>);
+/// Looping engine sound while the ship thrusts.
+#[derive(Component)]
+pub struct ThrustAudio;
 
 #[derive(Component)]
 pub struct BGMAudio;
 
-pub fn setup_audio(mut commands: Commands, asset_server: Res<AssetServer>) {
-    // Spawn background music
-    commands.spawn((
-        AudioBundle {
-            source: asset_server.load("embedded://Files/HoliznaCC0_GameBOI1.mp3"),
-            settings: PlaybackSettings {
-                mode: PlaybackMode::Loop,
-                ..default()
-            },
-        },
-        BGMAudio,
-    ));
+/// Fades a sound's volume towards a target level. Despawns the sound once faded out.
+#[derive(Component)]
+pub struct Fade {
+    pub level: f32,
+    pub target: f32,
+}
+
+impl Fade {
+    pub fn fade_in() -> Self {
+        Self {
+            level: 0.0,
+            target: 1.0,
+        }
+    }
+}
+
+/// Mixer channel a sound plays on. Each has its own volume and mute setting.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize)]
+pub enum AudioChannel {
+    Music,
+    Sfx,
+    Ui,
+}
+
+/// Request to play a one-off sound. Sounds with a position are panned by where they happen.
+#[derive(Event)]
+pub struct PlaySound {
+    pub event: SoundEvent,
+    pub position: Option<Vec2>,
+}
+
+impl PlaySound {
+    pub fn new(event: SoundEvent) -> Self {
+        Self {
+            event,
+            position: None,
+        }
+    }
+
+    pub fn at(event: SoundEvent, position: Vec2) -> Self {
+        Self {
+            event,
+            position: Some(position),
+        }
+    }
+}
+
+/// Playing instances of each sound event, oldest first. Events sharing a file are limited separately.
+#[derive(Resource, Default)]
+pub struct ActiveSounds(HashMap<SoundEvent, VecDeque<Entity>>);
+
+/// Loaded sounds and their playing instances.
+#[derive(SystemParam)]
+pub struct SoundPool<'w, 's> {
+    sounds: Res<'w, Sounds>,
+    active: ResMut<'w, ActiveSounds>,
+    playing: Query<'w, 's, (), With<AudioChannel>>,
+}
+
+impl SoundPool<'_, '_> {
+    /// Forget sounds that finished and despawned.
+    fn forget_finished(&mut self) {
+        let playing = &self.playing;
+        self.active.0.retain(|_, instances| {
+            instances.retain(|entity| playing.contains(*entity));
+            !instances.is_empty()
+        });
+    }
+}
+
+/// The ship that positioned sounds fade with distance from, and the camera that hears them.
+#[derive(SystemParam)]
+pub struct SoundListeners<'w, 's> {
+    playfield: Res<'w, Playfield>,
+    ship_query: Query<'w, 's, &'static Position, With<Player>>,
+    listener_query: Query<'w, 's, &'static Transform, With<SpatialListener>>,
+}
+
+impl SoundListeners<'_, '_> {
+    /// Volume falloff of a sound with distance from the ship.
+    fn attenuation(&self, position: Vec2) -> f32 {
+        let Ok(ship_position) = self.ship_query.get_single() else {
+            return 1.0;
+        };
+        let max_distance = self.playfield.view.length() / 2.0;
+        let distance = self
+            .playfield
+            .wrapped_delta(position - ship_position.0)
+            .length()
+            .min(max_distance);
+        1.0 - (1.0 - MIN_ATTENUATED_VOLUME) * distance / max_distance
+    }
+
+    /// Where to place a sound's emitter so it pans by its offset from the listener,
+    /// measured across edges like collisions.
+    fn emitter_translation(&self, position: Vec2) -> Vec3 {
+        let listener = self
+            .listener_query
+            .get_single()
+            .map_or(Vec3::ZERO, |transform| transform.translation);
+        // Only pan horizontally. A vertical offset would also lower the volume.
+        let offset = self.playfield.wrapped_delta(position - listener.truncate());
+        listener + Vec3::X * offset.x
+    }
+}
 
+/// Volume of a channel before the global volume is applied.
+pub fn channel_volume(settings: &Settings, channel: AudioChannel, app_state: &AppState) -> f32 {
+    let volume = settings.channel_volume(channel);
+    match (channel, app_state) {
+        (AudioChannel::Music, AppState::Paused) => volume * MUSIC_DUCK_VOLUME,
+        _ => volume,
+    }
+}
+
+pub fn setup_audio(mut commands: Commands, asset_server: Res<AssetServer>) {
     // Store audio handles for later.
-    commands.insert_resource(BulletFiredAudio(
-        asset_server.load("embedded://Files/shoot.ogg"),
+    commands.insert_resource(Sounds(
+        SoundEvent::iter()
+            .map(|event| (event, asset_server.load(event.path())))
+            .collect(),
     ));
-    commands.insert_resource(AsteroidDestroyedAudio(
-        asset_server.load("embedded://Files/hurt.ogg"),
-    ));
-    commands.insert_resource(ShipDestroyedAudio(
-        asset_server.load("embedded://Files/boom.ogg"),
-    ));
-    // TODO: Win audio.
-    commands.insert_resource(VictoryAudio(asset_server.load("embedded://Files/win.ogg")));
-    commands.insert_resource(LossAudio(asset_server.load("embedded://Files/lose.ogg")));
+}
+
+/// Spawn requested sounds at their channel's volume, stopping the oldest instance past the limit.
+pub fn play_sounds(
+    mut commands: Commands,
+    mut sound_events: EventReader<PlaySound>,
+    mut pool: SoundPool,
+    listeners: SoundListeners,
+    settings: Res<Settings>,
+    app_state: Res<State<AppState>>,
+) {
+    pool.forget_finished();
+
+    for PlaySound { event, position } in sound_events.read() {
+        let source = pool.sounds[event].clone();
+        let instances = pool.active.0.entry(*event).or_default();
+        while instances.len() >= MAX_SOUND_INSTANCES {
+            if let Some(oldest) = instances.pop_front() {
+                commands.entity(oldest).despawn();
+            }
+        }
+
+        let mut volume = channel_volume(&settings, event.channel(), app_state.get());
+        if let (Some(position), true) = (position, settings.sound_attenuation) {
+            volume *= listeners.attenuation(*position);
+        }
+        let mut sound = commands.spawn((
+            AudioBundle {
+                source,
+                settings: PlaybackSettings::DESPAWN
+                    .with_volume(Volume::new_relative(volume))
+                    .with_spatial(position.is_some()),
+            },
+            event.channel(),
+        ));
+        if let Some(position) = position {
+            sound.insert(TransformBundle::from_transform(
+                Transform::from_translation(listeners.emitter_translation(*position)),
+            ));
+        }
+        let entity = sound.id();
+        instances.push_back(entity);
+    }
+}
+
+/// Apply channel volumes to playing sounds. Music is ducked while paused.
+pub fn update_channel_volumes(
+    settings: Res<Settings>,
+    app_state: Res<State<AppState>>,
+    sink_query: Query<(&AudioSink, &AudioChannel, Option<&Fade>)>,
+) {
+    for (sink, channel, fade) in &sink_query {
+        let fade_level = fade.map_or(1.0, |fade| fade.level);
+        // Sinks ignore global volume once playing so apply it here.
+        sink.set_volume(
+            settings.master_volume
+                * channel_volume(&settings, *channel, app_state.get())
+                * fade_level,
+        );
+    }
+}
+
+pub fn fade_sounds(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    app_state: Res<State<AppState>>,
+    mut sink_query: Query<(Entity, &AudioSink, &AudioChannel, &mut Fade)>,
+) {
+    let step = time.delta_seconds() / FADE_SECS;
+    for (entity, sink, channel, mut fade) in &mut sink_query {
+        if fade.target == 0.0 && fade.level == 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if fade.level == fade.target {
+            continue;
+        }
+        fade.level = if fade.level < fade.target {
+            (fade.level + step).min(fade.target)
+        } else {
+            (fade.level - step).max(fade.target)
+        };
+        sink.set_volume(
+            settings.master_volume
+                * channel_volume(&settings, *channel, app_state.get())
+                * fade.level,
+        );
+    }
+}
+
+/// Loop the thrust sound while the thrust action is held.
+pub fn update_thrust_audio(
+    mut commands: Commands,
+    sounds: Res<Sounds>,
+    settings: Res<Settings>,
+    app_state: Res<State<AppState>>,
+    ship_query: Query<&ShipControls, With<Player>>,
+    thrust_query: Query<Entity, With<ThrustAudio>>,
+) {
+    let thrusting = ship_query.iter().any(|controls| controls.thrust);
+    match (thrusting, thrust_query.get_single()) {
+        (true, Err(_)) => {
+            let channel = SoundEvent::Thrust.channel();
+            let volume = channel_volume(&settings, channel, app_state.get());
+            commands.spawn((
+                AudioBundle {
+                    source: sounds[&SoundEvent::Thrust].clone(),
+                    settings: PlaybackSettings::LOOP.with_volume(Volume::new_relative(volume)),
+                },
+                channel,
+                ThrustAudio,
+            ));
+        }
+        (false, Ok(entity)) => commands.entity(entity).despawn(),
+        _ => {}
+    }
+}
+
+pub fn stop_thrust_audio(mut commands: Commands, thrust_query: Query<Entity, With<ThrustAudio>>) {
+    for entity in &thrust_query {
+        commands.entity(entity).despawn();
+    }
 }
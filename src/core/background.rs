@@ -0,0 +1,174 @@
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::shape,
+        render_resource::{AsBindGroup, Extent3d, ShaderRef, TextureDimension, TextureFormat},
+        texture::{ImageAddressMode, ImageLoaderSettings, ImageSampler, ImageSamplerDescriptor},
+    },
+    sprite::{Material2d, MaterialMesh2dBundle},
+};
+use rand::Rng;
+
+use super::{camera::FixedToCamera, player::Player, position::Playfield, velocity::Velocity};
+
+const SPACE_TEXTURE: &str = "embedded://Textures/tb_space.png";
+const STARFIELD_SHADER: &str = "embedded://Shaders/starfield.wgsl";
+/// Size in pixels of one tile of each layer's texture.
+const TILE_SIZE: u32 = 256;
+/// Extra quad size past the playfield so screen shake doesn't show its edges.
+const BACKGROUND_MARGIN: f32 = 1.1;
+
+/// Generated layer of stars over the space texture.
+struct StarLayerDef {
+    stars: usize,
+    /// Star side length in pixels.
+    size: u32,
+    brightness: f32,
+    depth: f32,
+}
+
+/// Far to near. Nearer layers have bigger, brighter stars and scroll faster.
+const STAR_LAYERS: [StarLayerDef; 2] = [
+    StarLayerDef {
+        stars: 40,
+        size: 1,
+        brightness: 0.6,
+        depth: 0.15,
+    },
+    StarLayerDef {
+        stars: 12,
+        size: 2,
+        brightness: 1.0,
+        depth: 0.35,
+    },
+];
+const SPACE_DEPTH: f32 = 0.05;
+
+/// Repeating texture scrolled across a quad.
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+pub struct StarfieldMaterial {
+    /// Scroll in tiles.
+    #[uniform(0)]
+    offset: Vec2,
+    /// Tiles across the quad.
+    #[uniform(0)]
+    repeat: Vec2,
+    #[texture(1)]
+    #[sampler(2)]
+    texture: Handle<Image>,
+}
+
+impl Material2d for StarfieldMaterial {
+    fn fragment_shader() -> ShaderRef {
+        STARFIELD_SHADER.into()
+    }
+}
+
+/// Background layer scrolled by a fraction of the ship's velocity.
+#[derive(Component)]
+pub struct ParallaxLayer {
+    depth: f32,
+}
+
+fn repeating_sampler() -> ImageSampler {
+    ImageSampler::Descriptor(ImageSamplerDescriptor {
+        address_mode_u: ImageAddressMode::Repeat,
+        address_mode_v: ImageAddressMode::Repeat,
+        ..default()
+    })
+}
+
+/// Transparent tile with stars scattered at random.
+fn star_image(layer: &StarLayerDef, rng: &mut impl Rng) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: TILE_SIZE,
+            height: TILE_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    for _ in 0..layer.stars {
+        let brightness = (rng.gen_range(0.5..=1.0) * layer.brightness * 255.0) as u8;
+        let (x, y) = (
+            rng.gen_range(0..TILE_SIZE - layer.size),
+            rng.gen_range(0..TILE_SIZE - layer.size),
+        );
+        for py in y..y + layer.size {
+            for px in x..x + layer.size {
+                let i = ((py * TILE_SIZE + px) * 4) as usize;
+                image.data[i..i + 4].copy_from_slice(&[brightness, brightness, brightness, 255]);
+            }
+        }
+    }
+    image.sampler = repeating_sampler();
+    image
+}
+
+pub fn setup_background(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    playfield: Res<Playfield>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StarfieldMaterial>>,
+) {
+    let size = playfield.view * BACKGROUND_MARGIN;
+    let mesh = meshes.add(shape::Quad::new(size).into());
+    let repeat = size / TILE_SIZE as f32;
+
+    let space_texture = asset_server
+        .load_with_settings(SPACE_TEXTURE, |settings: &mut ImageLoaderSettings| {
+            settings.sampler = repeating_sampler()
+        });
+    let mut rng = rand::thread_rng();
+    let star_layers = STAR_LAYERS
+        .iter()
+        .map(|layer| (images.add(star_image(layer, &mut rng)), layer.depth));
+
+    // One quad per layer. Stacked far to near under gameplay.
+    for (i, (texture, depth)) in [(space_texture, SPACE_DEPTH)]
+        .into_iter()
+        .chain(star_layers)
+        .enumerate()
+    {
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: mesh.clone().into(),
+                material: materials.add(StarfieldMaterial {
+                    offset: Vec2::ZERO,
+                    repeat,
+                    texture,
+                }),
+                transform: Transform::from_xyz(0.0, 0.0, i as f32 * 0.1),
+                ..default()
+            },
+            ParallaxLayer { depth },
+            FixedToCamera::default(),
+        ));
+    }
+}
+
+/// Scroll layers opposite to the ship's movement. Nearer layers scroll faster.
+pub fn scroll_background(
+    ship_query: Query<&Velocity, With<Player>>,
+    layer_query: Query<(&ParallaxLayer, &Handle<StarfieldMaterial>)>,
+    mut materials: ResMut<Assets<StarfieldMaterial>>,
+) {
+    let Ok(velocity) = ship_query.get_single() else {
+        return;
+    };
+    if velocity.0 == Vec2::ZERO {
+        return;
+    }
+    for (layer, handle) in &layer_query {
+        let Some(material) = materials.get_mut(handle) else {
+            continue;
+        };
+        // Texture v runs down the screen. Keep the offset within a tile as the texture repeats.
+        let scroll = Vec2::new(velocity.x, -velocity.y) * layer.depth / TILE_SIZE as f32;
+        material.offset = (material.offset + scroll).fract();
+    }
+}
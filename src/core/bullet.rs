@@ -11,9 +11,6 @@ pub struct Bullet {
     pub dst_traveled: f32,
 }
 
-#[derive(Resource, Deref)]
-pub struct BulletImage(pub Handle<Image>);
-
 /// Remove bullets after they travel some distance.
 pub fn remove_bullets(mut commands: Commands, mut query: Query<(Entity, &mut Bullet, &Position)>) {
     for (entity, mut bullet, pos) in &mut query {
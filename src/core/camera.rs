@@ -0,0 +1,266 @@
+use std::time::Duration;
+
+use bevy::{
+    core_pipeline::clear_color::ClearColorConfig,
+    prelude::*,
+    render::{
+        camera::{CameraOutputMode, ScalingMode, Viewport},
+        render_resource::{BlendState, LoadOp},
+        view::RenderLayers,
+    },
+    window::PrimaryWindow,
+};
+use rand::Rng;
+
+use super::{
+    asteroid::AsteroidSize,
+    player::Player,
+    position::{Playfield, Position},
+    settings::Settings,
+};
+
+/// Trauma removed per second.
+const TRAUMA_DECAY: f32 = 1.5;
+/// Camera offset and roll at full trauma.
+const MAX_SHAKE_OFFSET: f32 = 12.0;
+const MAX_SHAKE_ANGLE: f32 = 0.05;
+/// How quickly the camera catches up to the ship when following it.
+const FOLLOW_SMOOTHING: f32 = 5.0;
+/// Layer only the UI camera sees, so it draws no sprites or gizmos.
+const UI_RENDER_LAYER: u8 = 1;
+/// Window size the menus are laid out for.
+const UI_REFERENCE_SIZE: Vec2 = Vec2::new(1200.0, 640.0);
+
+/// Events that shake the camera. Big ones briefly freeze gameplay.
+#[derive(Event, Clone, Copy)]
+pub enum Impact {
+    AsteroidDestroyed(AsteroidSize),
+    /// Explosive asteroid blowing up.
+    Explosion,
+    ShipDestroyed,
+}
+
+impl Impact {
+    fn trauma(&self) -> f32 {
+        match self {
+            Impact::AsteroidDestroyed(AsteroidSize::Large) => 0.45,
+            Impact::AsteroidDestroyed(AsteroidSize::Medium) => 0.3,
+            Impact::AsteroidDestroyed(AsteroidSize::Small) => 0.15,
+            Impact::AsteroidDestroyed(AsteroidSize::Tiny) => 0.08,
+            Impact::Explosion => 0.5,
+            Impact::ShipDestroyed => 0.8,
+        }
+    }
+
+    fn hit_stop(&self) -> Option<Duration> {
+        match self {
+            Impact::AsteroidDestroyed(AsteroidSize::Large) => Some(Duration::from_millis(50)),
+            Impact::Explosion => Some(Duration::from_millis(60)),
+            Impact::ShipDestroyed => Some(Duration::from_millis(120)),
+            Impact::AsteroidDestroyed(_) => None,
+        }
+    }
+}
+
+/// Trauma based screen shake. Shake grows with the square of trauma.
+#[derive(Component, Default)]
+pub struct CameraShake {
+    trauma: f32,
+    /// Offset applied last frame, removed before applying the next.
+    offset: Vec2,
+}
+
+/// Point the camera looks at before shake is applied.
+#[derive(Component, Default)]
+pub struct CameraFocus(pub Vec2);
+
+/// Moves with the camera at an offset from its focus, like the background.
+#[derive(Component, Default)]
+pub struct FixedToCamera(pub Vec2);
+
+/// Time left with gameplay frozen.
+#[derive(Resource, Default)]
+pub struct HitStop(Option<Timer>);
+
+pub fn setup_camera(mut commands: Commands, playfield: Res<Playfield>) {
+    // 2D orthographic camera showing the whole view. Its viewport is letterboxed to the view's shape,
+    // and the clear color fills the bars.
+    let mut camera = Camera2dBundle::default();
+    camera.projection.scaling_mode = ScalingMode::AutoMin {
+        min_width: playfield.view.x,
+        min_height: playfield.view.y,
+    };
+    camera.camera_2d.clear_color = ClearColorConfig::Custom(Color::BLACK);
+    // Ears at the screen edges to pan sounds by position.
+    commands.spawn((
+        camera,
+        SpatialListener::new(playfield.view.x),
+        CameraShake::default(),
+        CameraFocus::default(),
+        // UI lays out over the whole window, so it's drawn by its own camera.
+        UiCameraConfig { show_ui: false },
+    ));
+
+    // Camera drawing only the UI over the whole window, blended over the game.
+    commands.spawn((
+        Camera2dBundle {
+            camera: Camera {
+                order: 1,
+                output_mode: CameraOutputMode::Write {
+                    blend_state: Some(BlendState::ALPHA_BLENDING),
+                    color_attachment_load_op: LoadOp::Load,
+                },
+                ..default()
+            },
+            camera_2d: Camera2d {
+                clear_color: ClearColorConfig::Custom(Color::NONE),
+            },
+            ..default()
+        },
+        RenderLayers::layer(UI_RENDER_LAYER),
+    ));
+}
+
+/// Letterbox or pillarbox the game camera to the view's shape, centered in the window.
+pub fn fit_viewport_to_window(
+    playfield: Res<Playfield>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut camera_query: Query<&mut Camera, With<CameraFocus>>,
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    let window_size = UVec2::new(window.physical_width(), window.physical_height());
+    // Minimized.
+    if window_size.min_element() == 0 {
+        return;
+    }
+    let scale = (window_size.as_vec2() / playfield.view).min_element();
+    let physical_size = (playfield.view * scale).round().as_uvec2().min(window_size);
+    let viewport = Viewport {
+        physical_position: (window_size - physical_size) / 2,
+        physical_size,
+        ..default()
+    };
+    for mut camera in &mut camera_query {
+        let changed = camera.viewport.as_ref().is_none_or(|current| {
+            current.physical_position != viewport.physical_position
+                || current.physical_size != viewport.physical_size
+        });
+        if changed {
+            camera.viewport = Some(viewport.clone());
+        }
+    }
+}
+
+/// Scale the UI with the window so menus fit any window or playfield size.
+pub fn fit_ui_to_window(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    let size = Vec2::new(window.width(), window.height()) / UI_REFERENCE_SIZE;
+    let scale = size.min_element() as f64;
+    if ui_scale.0 != scale && scale > 0.0 {
+        ui_scale.0 = scale;
+    }
+}
+
+pub fn handle_impacts(
+    mut impacts: EventReader<Impact>,
+    mut hit_stop: ResMut<HitStop>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut shake_query: Query<&mut CameraShake>,
+) {
+    for impact in impacts.read() {
+        for mut shake in &mut shake_query {
+            shake.trauma = (shake.trauma + impact.trauma()).min(1.0);
+        }
+        let Some(duration) = impact.hit_stop() else {
+            continue;
+        };
+        // Keep the longer of overlapping hit-stops.
+        if hit_stop
+            .0
+            .as_ref()
+            .is_none_or(|timer| timer.remaining() < duration)
+        {
+            hit_stop.0 = Some(Timer::new(duration, TimerMode::Once));
+            // FixedUpdate only advances with virtual time.
+            virtual_time.pause();
+        }
+    }
+}
+
+/// Resume gameplay once the hit-stop ends. Uses real time as virtual time is paused.
+pub fn update_hit_stop(
+    real_time: Res<Time<Real>>,
+    mut hit_stop: ResMut<HitStop>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    let Some(timer) = &mut hit_stop.0 else {
+        return;
+    };
+    if timer.tick(real_time.delta()).finished() {
+        hit_stop.0 = None;
+        virtual_time.unpause();
+    }
+}
+
+/// Smoothly track the ship across the world when it's larger than the view.
+pub fn follow_ship(
+    time: Res<Time>,
+    playfield: Res<Playfield>,
+    ship_query: Query<&Position, With<Player>>,
+    mut camera_query: Query<(&mut CameraFocus, &CameraShake, &mut Transform)>,
+    mut fixed_query: Query<(&FixedToCamera, &mut Transform), Without<CameraFocus>>,
+) {
+    if !playfield.follows_ship() {
+        return;
+    }
+    let Ok((mut focus, shake, mut transform)) = camera_query.get_single_mut() else {
+        return;
+    };
+    if let Ok(ship_pos) = ship_query.get_single() {
+        // Take the short way around so the camera follows the ship across edges.
+        let delta = playfield.wrapped_delta(ship_pos.0 - focus.0);
+        let t = 1.0 - (-FOLLOW_SMOOTHING * time.delta_seconds()).exp();
+        focus.0 = playfield.wrap(focus.0 + delta * t, 0.0);
+    }
+
+    transform.translation = (focus.0 + shake.offset).extend(transform.translation.z);
+    for (fixed, mut fixed_transform) in &mut fixed_query {
+        fixed_transform.translation = (focus.0 + fixed.0).extend(fixed_transform.translation.z);
+    }
+}
+
+/// Offset and roll the camera by its trauma. Keeps shaking through hit-stops.
+pub fn shake_camera(
+    real_time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    mut camera_query: Query<(&mut CameraShake, &mut Transform)>,
+) {
+    let mut rng = rand::thread_rng();
+    for (mut shake, mut transform) in &mut camera_query {
+        shake.trauma = (shake.trauma - TRAUMA_DECAY * real_time.delta_seconds()).max(0.0);
+        let amount = if settings.screen_shake {
+            shake.trauma.powi(2)
+        } else {
+            0.0
+        };
+
+        let offset = if amount > 0.0 {
+            Vec2::new(rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0))
+                * MAX_SHAKE_OFFSET
+                * amount
+        } else {
+            Vec2::ZERO
+        };
+        let angle = MAX_SHAKE_ANGLE * amount * rng.gen_range(-1.0..=1.0);
+        transform.translation += (offset - shake.offset).extend(0.0);
+        transform.rotation = Quat::from_rotation_z(angle);
+        shake.offset = offset;
+    }
+}
@@ -1,130 +1,212 @@
-use bevy::prelude::*;
+use std::collections::{HashSet, VecDeque};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
 
 use super::{
-    asteroid::{Asteroid, AsteroidImages, AsteroidSize, ASTEROID_SPLIT_NUM, ASTEROID_VELOCITY},
-    audio::{AsteroidDestroyedAudio, LossAudio, ShipDestroyedAudio, VictoryAudio},
+    asteroid::{Asteroid, AsteroidDefs, AsteroidSpawner, Health},
+    audio::{PlaySound, SoundEvent},
     bullet::Bullet,
+    camera::Impact,
+    lives::{respawn_ship, Invulnerable, Lives},
+    particles::{EmitParticles, ParticleEffect},
     player::Player,
-    position::Position,
+    position::{Playfield, Position},
+    score::{AwardPoints, ScorePopup},
+    settings::Settings,
+    vector::{Outline, Theme},
     velocity::Velocity,
-    AppState,
+    AppState, NextStates,
 };
-use crate::{ui::menu::MenuState, GetRandom};
+use crate::ui::menu::MenuState;
+
+/// Sounds, particles and camera feedback for hits.
+#[derive(SystemParam)]
+pub struct ImpactEffects<'w> {
+    pub sounds: EventWriter<'w, PlaySound>,
+    pub particles: EventWriter<'w, EmitParticles>,
+    pub impacts: EventWriter<'w, Impact>,
+}
+
+/// Asteroid colliders, shaped by the theme.
+#[derive(SystemParam)]
+pub struct AsteroidHitboxes<'w> {
+    settings: Res<'w, Settings>,
+    playfield: Res<'w, Playfield>,
+    defs: Res<'w, AsteroidDefs>,
+}
+
+impl AsteroidHitboxes<'_> {
+    /// Whether a circle overlaps an asteroid. The vector theme collides with the asteroid's outline.
+    /// Measured across edges so objects on opposite sides of a seam can touch.
+    fn overlaps(
+        &self,
+        asteroid: &Asteroid,
+        outline: &Outline,
+        asteroid_pos: Vec2,
+        pos: Vec2,
+        radius: f32,
+    ) -> bool {
+        let offset = self.playfield.wrapped_delta(pos - asteroid_pos);
+        match self.settings.theme {
+            Theme::Sprites => offset.length() < radius + self.defs[&asteroid.size].radius,
+            Theme::Vector => outline.overlaps_circle(offset, radius),
+        }
+    }
+}
+
+/// Ships that asteroids can destroy.
+type Vulnerable = (With<Player>, Without<Invulnerable>);
 
 pub fn detect_asteroid_ship_collisions(
     mut commands: Commands,
-    ship_query: Query<(&Transform, &Position), With<Player>>,
-    asteroid_query: Query<(Entity, &Asteroid, &Position), With<Asteroid>>,
-    ship_destroyed_audio: Res<ShipDestroyedAudio>,
-    loss_audio: Res<LossAudio>,
-    mut menu_state: ResMut<NextState<MenuState>>,
-    mut game_state: ResMut<NextState<AppState>>,
+    hitboxes: AsteroidHitboxes,
+    mut lives: ResMut<Lives>,
+    mut ship_query: Query<(Entity, &Transform, &mut Position, &mut Velocity), Vulnerable>,
+    asteroid_query: Query<(&Asteroid, &Outline, &Position), Without<Player>>,
+    mut effects: ImpactEffects,
+    mut next_states: NextStates,
 ) {
-    let Ok((ship_transform, ship_pos)) = ship_query.get_single() else {
+    let Ok((ship, ship_transform, mut ship_pos, mut ship_velocity)) = ship_query.get_single_mut()
+    else {
         return;
     };
 
     let ship_size = ship_transform.scale.max_element();
-    for (_, asteroid, asteroid_pos) in &asteroid_query {
-        let asteroid_size = asteroid.size.scale();
-        let dst = (ship_pos.0 - asteroid_pos.0).length();
-
-        // Assume hitbox is circle. When centers of objects collide.
-        if dst < ship_size + asteroid_size {
-            // Play ship destroyed sound once and despawn entity.
-            commands.spawn(AudioBundle {
-                source: ship_destroyed_audio.0.clone(),
-                settings: PlaybackSettings::DESPAWN,
-            });
+    for (asteroid, outline, asteroid_pos) in &asteroid_query {
+        // Assume ship hitbox is circle.
+        if hitboxes.overlaps(asteroid, outline, asteroid_pos.0, ship_pos.0, ship_size) {
+            // Play ship destroyed sound once.
+            effects
+                .sounds
+                .send(PlaySound::at(SoundEvent::ShipDestroyed, ship_pos.0));
+            effects.particles.send(EmitParticles::burst(
+                ParticleEffect::ShipExplosion,
+                ship_pos.0,
+            ));
+            effects.impacts.send(Impact::ShipDestroyed);
+            lives.0 = lives.0.saturating_sub(1);
+            if lives.0 > 0 {
+                respawn_ship(&mut commands, ship, &mut ship_pos, &mut ship_velocity);
+                return;
+            }
             // Then play loss audio.
-            commands.spawn(AudioBundle {
-                source: loss_audio.0.clone(),
-                settings: PlaybackSettings::DESPAWN,
-            });
-            menu_state.set(MenuState::Main);
-            game_state.set(AppState::Menu);
+            effects.sounds.send(PlaySound::new(SoundEvent::Loss));
+            next_states.set(AppState::Menu, MenuState::Main);
             return;
         }
     }
 }
 
+/// Hit on an asteroid from a bullet or blast.
+#[derive(Event)]
+pub struct DamageAsteroid(pub Entity);
+
 pub fn detect_asteroid_bullet_collisions(
     mut commands: Commands,
-    asteroid_images: Res<AsteroidImages>,
-    asteroid_destroyed_audio: Res<AsteroidDestroyedAudio>,
-    mut player_query: Query<&mut Player, With<Player>>,
-    mut bullet_query: Query<(Entity, &Transform, &Bullet, &Position), With<Bullet>>,
-    mut asteroid_query: Query<(Entity, &Asteroid, &Position), With<Asteroid>>,
+    hitboxes: AsteroidHitboxes,
+    mut damage_events: EventWriter<DamageAsteroid>,
+    bullet_query: Query<(Entity, &Transform, &Position), With<Bullet>>,
+    asteroid_query: Query<(Entity, &Asteroid, &Outline, &Position)>,
 ) {
-    let Ok(mut player) = player_query.get_single_mut() else {
-        return;
-    };
-    for (bullet_entity, bullet_transform, _bullet, bullet_pos) in &mut bullet_query {
+    for (bullet_entity, bullet_transform, bullet_pos) in &bullet_query {
         let bullet_size = bullet_transform.scale.max_element();
-        for (asteroid_entity, asteroid, asteroid_pos) in &mut asteroid_query {
-            let asteroid_size = asteroid.size.scale();
-            let dst = (bullet_pos.0 - asteroid_pos.0).length();
-
-            if dst < bullet_size + asteroid_size {
-                // TODO: Cause damage to asteroid?
-                commands.entity(bullet_entity).despawn();
-
-                // Get smaller sized asteroid and spawn more if possible.
-                // Smaller asteroids go in random direction.
-                // Otherwise, despawn smallest.
-                if let Some(new_asteroid_size) = usize::from(asteroid.size)
-                    .checked_sub(1)
-                    .map(AsteroidSize::from)
-                {
-                    let asteroid_img_handle = asteroid_images[&new_asteroid_size].clone();
-                    for _ in 0..ASTEROID_SPLIT_NUM {
-                        commands.spawn((
-                            Asteroid {
-                                size: new_asteroid_size,
-                            },
-                            SpriteBundle {
-                                texture: asteroid_img_handle.clone(),
-                                // Translation of 1.0 keeps asteroid over ship.
-                                transform: Transform::default().with_translation(Vec3::new(
-                                    asteroid_pos.x,
-                                    asteroid_pos.y,
-                                    1.0,
-                                )),
-                                ..default()
-                            },
-                            Velocity(Velocity::random().normalize() * ASTEROID_VELOCITY),
-                            Position(**asteroid_pos),
-                        ));
-                    }
-                }
-
-                commands.entity(asteroid_entity).despawn();
-                commands.spawn(AudioBundle {
-                    source: asteroid_destroyed_audio.0.clone(),
-                    settings: PlaybackSettings::DESPAWN,
-                });
-                // Each destroyed asteroid provides 1 pt.
-                player.score += 1
-            }
+        // Each bullet hits at most one asteroid.
+        let hit = asteroid_query
+            .iter()
+            .find(|(_, asteroid, outline, asteroid_pos)| {
+                hitboxes.overlaps(asteroid, outline, asteroid_pos.0, bullet_pos.0, bullet_size)
+            });
+        if let Some((asteroid_entity, ..)) = hit {
+            commands.entity(bullet_entity).despawn();
+            damage_events.send(DamageAsteroid(asteroid_entity));
         }
     }
 }
 
-pub fn check_win_condition(
+/// Apply hits to asteroids. Destroyed asteroids split and score, and explosive ones hit their neighbors.
+pub fn damage_asteroids(
     mut commands: Commands,
-    asteroid_query: Query<(Entity, &Asteroid), With<Asteroid>>,
-    victory_audio: Res<VictoryAudio>,
-    mut menu_state: ResMut<NextState<MenuState>>,
-    mut game_state: ResMut<NextState<AppState>>,
+    spawner: AsteroidSpawner,
+    mut damage_events: EventReader<DamageAsteroid>,
+    mut effects: ImpactEffects,
+    mut award_events: EventWriter<AwardPoints>,
+    mut asteroid_query: Query<(Entity, &Asteroid, &Position, &mut Health)>,
 ) {
-    if asteroid_query.is_empty() {
-        // Then play loss audio.
-        commands.spawn(AudioBundle {
-            source: victory_audio.0.clone(),
-            settings: PlaybackSettings::DESPAWN,
+    let mut hits: VecDeque<Entity> = damage_events.read().map(|event| event.0).collect();
+    let mut destroyed = HashSet::new();
+    while let Some(entity) = hits.pop_front() {
+        if destroyed.contains(&entity) {
+            continue;
+        }
+        let Ok((_, asteroid, asteroid_pos, mut health)) = asteroid_query.get_mut(entity) else {
+            continue;
+        };
+        let (asteroid, asteroid_pos) = (asteroid.clone(), asteroid_pos.clone());
+        health.0 = health.0.saturating_sub(1);
+        if health.0 > 0 {
+            effects
+                .sounds
+                .send(PlaySound::at(SoundEvent::AsteroidDamaged, asteroid_pos.0));
+            continue;
+        }
+
+        destroyed.insert(entity);
+        commands.entity(entity).despawn();
+        let def = &spawner.defs[&asteroid.size];
+        let material = &spawner.materials[&asteroid.material];
+
+        // Split into smaller asteroids of the same material going in random directions, if any.
+        if let Some(children) = def.children {
+            for _ in 0..children.count + material.extra_children {
+                let child = Asteroid {
+                    size: children.size,
+                    material: asteroid.material,
+                };
+                spawner.spawn(&mut commands, child, asteroid_pos.clone());
+            }
+        }
+
+        effects
+            .sounds
+            .send(PlaySound::at(SoundEvent::AsteroidDestroyed, asteroid_pos.0));
+        effects.particles.send(EmitParticles::burst(
+            ParticleEffect::Debris(def.radius),
+            asteroid_pos.0,
+        ));
+        effects
+            .impacts
+            .send(Impact::AsteroidDestroyed(asteroid.size));
+        award_events.send(AwardPoints {
+            points: def.score + material.score,
+            position: asteroid_pos.0,
         });
-        menu_state.set(MenuState::Main);
-        game_state.set(AppState::Menu);
+
+        if material.blast_radius > 0.0 {
+            effects
+                .sounds
+                .send(PlaySound::at(SoundEvent::AsteroidExploded, asteroid_pos.0));
+            effects.particles.send(EmitParticles::burst(
+                ParticleEffect::Blast(material.blast_radius),
+                asteroid_pos.0,
+            ));
+            effects.impacts.send(Impact::Explosion);
+            hits.extend(
+                asteroid_query
+                    .iter()
+                    .filter(|(other, other_asteroid, other_pos, _)| {
+                        let reach =
+                            material.blast_radius + spawner.defs[&other_asteroid.size].radius;
+                        *other != entity
+                            && !destroyed.contains(other)
+                            && spawner
+                                .playfield
+                                .wrapped_delta(other_pos.0 - asteroid_pos.0)
+                                .length()
+                                < reach
+                    })
+                    .map(|(other, ..)| other),
+            );
+        }
     }
 }
 
@@ -133,8 +215,9 @@ pub fn cleanup_game_entities(
     ship_query: Query<Entity, With<Player>>,
     bullet_query: Query<Entity, With<Bullet>>,
     asteroid_query: Query<Entity, With<Asteroid>>,
+    popup_query: Query<Entity, With<ScorePopup>>,
 ) {
-    // Despawn player, bullets, and asteroids.
+    // Despawn player, bullets, asteroids, and score popups.
     commands.entity(ship_query.single()).despawn();
     for asteroid_entity in &asteroid_query {
         commands.entity(asteroid_entity).despawn()
@@ -142,4 +225,7 @@ pub fn cleanup_game_entities(
     for bullet_entity in &bullet_query {
         commands.entity(bullet_entity).despawn()
     }
+    for popup_entity in &popup_query {
+        commands.entity(popup_entity).despawn()
+    }
 }
@@ -0,0 +1,263 @@
+use std::env;
+
+use bevy::prelude::*;
+
+use super::{
+    asteroid::{Asteroid, AsteroidDefs},
+    bullet::BULLET_VELOCITY,
+    input::{Action, RotationAxis},
+    player::Player,
+    position::{Playfield, Position},
+    velocity::Velocity,
+};
+
+const AUTOPILOT_ARG: &str = "--autopilot";
+/// Ticks ahead the autopilot looks for collisions.
+const COLLISION_HORIZON: f32 = 60.0;
+/// Ticks before a collision the autopilot stops shooting and flees.
+const EVADE_TICKS: f32 = 20.0;
+/// Clearance the autopilot keeps between the ship and asteroids.
+const EVADE_MARGIN: f32 = 30.0;
+/// Angle in radians off the aim point the autopilot still fires at.
+const AIM_TOLERANCE: f32 = 0.1;
+/// Angle in radians off a heading the autopilot turns at full speed.
+const FULL_TURN_ANGLE: f32 = 0.5;
+/// Autopilot closes in on targets further than this.
+const APPROACH_DISTANCE: f32 = 350.0;
+
+/// What steers a ship.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ShipController {
+    /// Keyboard or gamepad.
+    #[default]
+    Input,
+    Autopilot,
+}
+
+/// Controller for the player's ship, chosen at launch.
+/// `--autopilot` lets the AI play, for soak tests and balancing runs.
+#[derive(Resource, Deref)]
+pub struct PlayerController(pub ShipController);
+
+impl PlayerController {
+    pub fn from_args() -> Self {
+        if env::args().any(|arg| arg == AUTOPILOT_ARG) {
+            Self(ShipController::Autopilot)
+        } else {
+            Self(ShipController::Input)
+        }
+    }
+}
+
+/// What a ship's controller wants it to do. Fire and hyperspace stay set until the ship acts on them.
+#[derive(Component, Debug, Default)]
+pub struct ShipControls {
+    /// From -1 to 1. Positive turns clockwise.
+    pub turn: f32,
+    pub thrust: bool,
+    pub fire: bool,
+    pub hyperspace: bool,
+}
+
+/// Set controls of input controlled ships from actions.
+pub fn read_input_controls(
+    actions: Res<Input<Action>>,
+    rotation: Res<RotationAxis>,
+    mut ship_query: Query<(&ShipController, &mut ShipControls)>,
+) {
+    for (controller, mut controls) in &mut ship_query {
+        if *controller != ShipController::Input {
+            continue;
+        }
+        controls.turn = rotation.0;
+        controls.thrust = actions.pressed(Action::Thrust);
+        controls.fire |= actions.just_pressed(Action::Fire);
+        controls.hyperspace |= actions.just_pressed(Action::Hyperspace);
+    }
+}
+
+/// Ticks until a bullet fired now hits a target at `offset` moving at `velocity`, if it can.
+fn intercept_time(offset: Vec2, velocity: Vec2) -> Option<f32> {
+    // Earliest positive t where |offset + velocity * t| = BULLET_VELOCITY * t.
+    let a = velocity.length_squared() - BULLET_VELOCITY * BULLET_VELOCITY;
+    let b = 2.0 * offset.dot(velocity);
+    let c = offset.length_squared();
+    if a.abs() < f32::EPSILON {
+        return (b < 0.0).then(|| -c / b);
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    [(-b - root) / (2.0 * a), (-b + root) / (2.0 * a)]
+        .into_iter()
+        .filter(|time| *time > 0.0)
+        .min_by(f32::total_cmp)
+}
+
+/// Ticks until two objects are closest within the horizon, and their offset then.
+fn closest_approach(offset: Vec2, relative_velocity: Vec2) -> (f32, Vec2) {
+    let speed_squared = relative_velocity.length_squared();
+    let time = if speed_squared > 0.0 {
+        (-offset.dot(relative_velocity) / speed_squared).clamp(0.0, COLLISION_HORIZON)
+    } else {
+        0.0
+    };
+    (time, offset + relative_velocity * time)
+}
+
+/// Turn toward a heading. Returns the turn and the angle left to turn. A zero heading holds course.
+fn steer(ship: &Player, heading: Vec2) -> (f32, f32) {
+    let Some(heading) = heading.try_normalize() else {
+        return (0.0, 0.0);
+    };
+    // Positive turn is clockwise, against the angle.
+    let angle = ship.direction().angle_between(heading);
+    ((-angle / FULL_TURN_ANGLE).clamp(-1.0, 1.0), angle)
+}
+
+/// Set controls of autopilot ships. Flees imminent collisions,
+/// otherwise leads shots at the asteroid on a collision course soonest or the nearest.
+pub fn pilot_autopilot_ships(
+    playfield: Res<Playfield>,
+    asteroid_defs: Res<AsteroidDefs>,
+    asteroid_query: Query<(&Asteroid, &Position, &Velocity)>,
+    mut ship_query: Query<(
+        &ShipController,
+        &Player,
+        &Position,
+        &Velocity,
+        &mut ShipControls,
+    )>,
+) {
+    for (controller, ship, ship_pos, ship_velocity, mut controls) in &mut ship_query {
+        if *controller != ShipController::Autopilot {
+            continue;
+        }
+        controls.turn = 0.0;
+        controls.thrust = false;
+
+        // Offset and velocity of each asteroid, with when and where it's closest if too close.
+        let asteroids: Vec<_> = asteroid_query
+            .iter()
+            .map(|(asteroid, pos, velocity)| {
+                let offset = playfield.wrapped_delta(pos.0 - ship_pos.0);
+                let relative_velocity = velocity.0 - ship_velocity.0;
+                let (time, closest) = closest_approach(offset, relative_velocity);
+                let clearance = asteroid_defs[&asteroid.size].radius + EVADE_MARGIN;
+                let collision = (closest.length() < clearance).then_some((time, closest));
+                (offset, velocity.0, collision)
+            })
+            .collect();
+        let threat = asteroids
+            .iter()
+            .filter_map(|(offset, velocity, collision)| {
+                collision.map(|(time, closest)| (time, closest, *offset, *velocity))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+
+        if let Some((_, closest, offset, velocity)) =
+            threat.filter(|(time, ..)| *time < EVADE_TICKS)
+        {
+            // Move away from where the asteroid passes closest, or sideways if it's dead on.
+            let away = if closest.length() > 1.0 {
+                -closest
+            } else {
+                (velocity - ship_velocity.0).perp()
+            };
+            let (turn, angle) = steer(ship, away.try_normalize().unwrap_or(-offset));
+            controls.turn = turn;
+            controls.thrust = angle.abs() < FULL_TURN_ANGLE;
+            continue;
+        }
+
+        // Target the asteroid on a collision course, otherwise the nearest.
+        let target = threat
+            .map(|(_, _, offset, velocity)| (offset, velocity))
+            .or_else(|| {
+                asteroids
+                    .iter()
+                    .min_by(|a, b| a.0.length_squared().total_cmp(&b.0.length_squared()))
+                    .map(|(offset, velocity, _)| (*offset, *velocity))
+            });
+        let Some((offset, velocity)) = target else {
+            continue;
+        };
+        let aim = intercept_time(offset, velocity).map_or(offset, |time| offset + velocity * time);
+        let (turn, angle) = steer(ship, aim);
+        controls.turn = turn;
+        if angle.abs() > AIM_TOLERANCE {
+            continue;
+        }
+        controls.thrust = offset.length() > APPROACH_DISTANCE;
+        // Only ask to fire when the ship can, so held shots don't fire late.
+        controls.fire |= ship.can_fire();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn intercepts_stationary_target() {
+        let time = intercept_time(Vec2::new(100.0, 0.0), Vec2::ZERO).unwrap();
+        assert!((time - 100.0 / BULLET_VELOCITY).abs() < 1e-3);
+    }
+
+    #[test]
+    fn cannot_intercept_target_outrunning_bullet() {
+        let velocity = Vec2::new(BULLET_VELOCITY * 2.0, 0.0);
+        assert_eq!(intercept_time(Vec2::new(100.0, 0.0), velocity), None);
+    }
+
+    #[test]
+    fn intercepts_head_on_target_at_closing_speed() {
+        // Coming straight at the ship as fast as a bullet.
+        let velocity = Vec2::new(-BULLET_VELOCITY, 0.0);
+        let time = intercept_time(Vec2::new(100.0, 0.0), velocity).unwrap();
+        assert!((time - 100.0 / (2.0 * BULLET_VELOCITY)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn closest_approach_of_stationary_target_is_now() {
+        let offset = Vec2::new(30.0, 40.0);
+        assert_eq!(closest_approach(offset, Vec2::ZERO), (0.0, offset));
+    }
+
+    #[test]
+    fn closest_approach_of_head_on_target_is_a_collision() {
+        let (time, closest) = closest_approach(Vec2::new(100.0, 0.0), Vec2::new(-5.0, 0.0));
+        assert_eq!(time, 20.0);
+        assert!(closest.length() < 1e-3);
+    }
+
+    #[test]
+    fn closest_approach_is_limited_to_horizon() {
+        let (time, closest) = closest_approach(Vec2::new(1000.0, 10.0), Vec2::new(-1.0, 0.0));
+        assert_eq!(time, COLLISION_HORIZON);
+        assert_eq!(closest, Vec2::new(1000.0 - COLLISION_HORIZON, 10.0));
+    }
+
+    #[test]
+    fn steer_holds_course_on_heading() {
+        let ship = Player::default();
+        assert_eq!(steer(&ship, ship.direction() * 50.0), (0.0, 0.0));
+    }
+
+    #[test]
+    fn steer_turns_clockwise_toward_right() {
+        // Facing up with the heading to the right.
+        let (turn, angle) = steer(&Player::default(), Vec2::X);
+        assert_eq!(turn, 1.0);
+        assert!((angle + FRAC_PI_2).abs() < 1e-3);
+    }
+
+    #[test]
+    fn steer_ignores_zero_heading() {
+        assert_eq!(steer(&Player::default(), Vec2::ZERO), (0.0, 0.0));
+    }
+}
@@ -0,0 +1,277 @@
+use bevy::{
+    core_pipeline::{
+        bloom::BloomSettings, core_2d, fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+        tonemapping::Tonemapping,
+    },
+    ecs::query::QueryItem,
+    prelude::*,
+    render::{
+        extract_component::{ComponentUniforms, ExtractComponentPlugin, UniformComponentPlugin},
+        render_graph::{
+            NodeRunError, RenderGraphApp, RenderGraphContext, ViewNode, ViewNodeRunner,
+        },
+        render_resource::{
+            BindGroupEntries, BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry,
+            BindingType, BufferBindingType, CachedRenderPipelineId, ColorTargetState, ColorWrites,
+            FragmentState, MultisampleState, Operations, PipelineCache, PrimitiveState,
+            RenderPassColorAttachment, RenderPassDescriptor, RenderPipelineDescriptor, Sampler,
+            SamplerBindingType, SamplerDescriptor, ShaderStages, ShaderType, TextureFormat,
+            TextureSampleType, TextureViewDimension,
+        },
+        renderer::{RenderContext, RenderDevice},
+        texture::BevyDefault,
+        view::ViewTarget,
+        RenderApp,
+    },
+};
+
+use super::{camera::CameraFocus, settings::Settings};
+
+const CRT_SHADER: &str = "embedded://Shaders/crt.wgsl";
+/// Effect strength at full settings.
+const MAX_BLOOM_INTENSITY: f32 = 0.5;
+const MAX_SCANLINE_DARKNESS: f32 = 0.4;
+const MAX_BARREL_DISTORTION: f32 = 0.12;
+const MAX_CHROMATIC_ABERRATION: f32 = 0.006;
+
+pub use uniform::CrtSettings;
+
+// Newer compilers flag the field checks ShaderType derives as unused.
+#[allow(dead_code)]
+mod uniform {
+    use bevy::{
+        prelude::*,
+        render::{extract_component::ExtractComponent, render_resource::ShaderType},
+    };
+
+    /// Scanlines, barrel distortion and chromatic aberration drawn over the camera.
+    /// Only on cameras with at least one effect enabled.
+    #[derive(Component, Clone, Copy, PartialEq, ExtractComponent, ShaderType)]
+    pub struct CrtSettings {
+        pub scanlines: f32,
+        pub distortion: f32,
+        pub aberration: f32,
+    }
+}
+
+impl CrtSettings {
+    fn from_settings(settings: &Settings) -> Option<Self> {
+        let crt = Self {
+            scanlines: settings.scanlines * MAX_SCANLINE_DARKNESS,
+            distortion: settings.barrel_distortion * MAX_BARREL_DISTORTION,
+            aberration: settings.chromatic_aberration * MAX_CHROMATIC_ABERRATION,
+        };
+        (crt.scanlines > 0.0 || crt.distortion > 0.0 || crt.aberration > 0.0).then_some(crt)
+    }
+}
+
+/// Adds the CRT pass after tonemapping in the 2D render graph.
+pub struct CrtPlugin;
+
+impl Plugin for CrtPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            ExtractComponentPlugin::<CrtSettings>::default(),
+            UniformComponentPlugin::<CrtSettings>::default(),
+        ))
+        .add_systems(
+            Update,
+            apply_post_process.run_if(resource_changed::<Settings>()),
+        );
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .add_render_graph_node::<ViewNodeRunner<CrtNode>>(core_2d::graph::NAME, CrtNode::NAME)
+            .add_render_graph_edges(
+                core_2d::graph::NAME,
+                &[
+                    core_2d::graph::node::TONEMAPPING,
+                    CrtNode::NAME,
+                    core_2d::graph::node::END_MAIN_PASS_POST_PROCESSING,
+                ],
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.init_resource::<CrtPipeline>();
+    }
+}
+
+#[derive(Default)]
+struct CrtNode;
+
+impl CrtNode {
+    const NAME: &'static str = "crt";
+}
+
+impl ViewNode for CrtNode {
+    type ViewQuery = (&'static ViewTarget, &'static CrtSettings);
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, _crt): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let crt_pipeline = world.resource::<CrtPipeline>();
+        let pipeline_id = if view_target.is_hdr() {
+            crt_pipeline.hdr_pipeline_id
+        } else {
+            crt_pipeline.pipeline_id
+        };
+        let (Some(pipeline), Some(settings_binding)) = (
+            world
+                .resource::<PipelineCache>()
+                .get_render_pipeline(pipeline_id),
+            world
+                .resource::<ComponentUniforms<CrtSettings>>()
+                .uniforms()
+                .binding(),
+        ) else {
+            // Shader still loading.
+            return Ok(());
+        };
+
+        // Read the rendered frame and write it back with the effects applied.
+        let post_process = view_target.post_process_write();
+        let bind_group = render_context.render_device().create_bind_group(
+            "crt_bind_group",
+            &crt_pipeline.layout,
+            &BindGroupEntries::sequential((
+                post_process.source,
+                &crt_pipeline.sampler,
+                settings_binding,
+            )),
+        );
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("crt_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+        });
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        // Fullscreen triangle.
+        render_pass.draw(0..3, 0..1);
+        Ok(())
+    }
+}
+
+/// CRT pipelines for the normal and HDR view formats. Bloom needs HDR.
+#[derive(Resource)]
+struct CrtPipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    pipeline_id: CachedRenderPipelineId,
+    hdr_pipeline_id: CachedRenderPipelineId,
+}
+
+impl FromWorld for CrtPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("crt_bind_group_layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: Some(CrtSettings::min_size()),
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let sampler = render_device.create_sampler(&SamplerDescriptor::default());
+        let shader = world.resource::<AssetServer>().load(CRT_SHADER);
+
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let queue_pipeline = |format: TextureFormat| {
+            pipeline_cache.queue_render_pipeline(RenderPipelineDescriptor {
+                label: Some("crt_pipeline".into()),
+                layout: vec![layout.clone()],
+                vertex: fullscreen_shader_vertex_state(),
+                fragment: Some(FragmentState {
+                    shader: shader.clone(),
+                    shader_defs: vec![],
+                    entry_point: "fragment".into(),
+                    targets: vec![Some(ColorTargetState {
+                        format,
+                        blend: None,
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                primitive: PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: MultisampleState::default(),
+                push_constant_ranges: vec![],
+            })
+        };
+        let pipeline_id = queue_pipeline(TextureFormat::bevy_default());
+        let hdr_pipeline_id = queue_pipeline(ViewTarget::TEXTURE_FORMAT_HDR);
+
+        Self {
+            layout,
+            sampler,
+            pipeline_id,
+            hdr_pipeline_id,
+        }
+    }
+}
+
+/// Add or remove bloom and the CRT pass on the camera to match settings.
+pub fn apply_post_process(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut camera_query: Query<(Entity, &mut Camera, &mut Tonemapping), With<CameraFocus>>,
+) {
+    for (entity, mut camera, mut tonemapping) in &mut camera_query {
+        let mut camera_commands = commands.entity(entity);
+        let bloom = settings.bloom > 0.0;
+        // Bloom reads brightness above 1.0 so needs an HDR target.
+        camera.hdr = bloom;
+        if bloom {
+            *tonemapping = Tonemapping::TonyMcMapface;
+            camera_commands.insert(BloomSettings {
+                intensity: settings.bloom * MAX_BLOOM_INTENSITY,
+                // Thresholded so only bright sprites glow.
+                ..BloomSettings::OLD_SCHOOL
+            });
+        } else {
+            *tonemapping = Tonemapping::None;
+            camera_commands.remove::<BloomSettings>();
+        }
+
+        match CrtSettings::from_settings(&settings) {
+            Some(crt) => camera_commands.insert(crt),
+            None => camera_commands.remove::<CrtSettings>(),
+        };
+    }
+}
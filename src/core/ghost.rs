@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+
+use super::{
+    camera::CameraFocus,
+    position::{Playfield, Position},
+};
+
+/// Edges a ghost is copied across. One ghost per combination.
+const GHOST_AXES: [BVec2; 3] = [
+    BVec2::new(true, false),
+    BVec2::new(false, true),
+    BVec2::TRUE,
+];
+/// Furthest a sprite extends from its position. Covers the largest asteroid.
+const GHOST_MARGIN: f32 = 64.0;
+
+/// Copy of a wrapping sprite drawn across the world's edges from it,
+/// so objects near a seam show on both sides.
+#[derive(Component)]
+pub struct Ghost {
+    source: Entity,
+    axes: BVec2,
+}
+
+/// Parts of a sprite a ghost copies from its source.
+type SourceSprite<'a> = (
+    &'a Transform,
+    &'a Visibility,
+    Option<&'a Sprite>,
+    Option<&'a TextureAtlasSprite>,
+    Option<&'a Handle<TextureAtlas>>,
+);
+
+/// A ghost and the parts of its sprite it copies into.
+type GhostSprite<'a> = (
+    Entity,
+    &'a Ghost,
+    &'a mut Transform,
+    &'a mut Visibility,
+    Option<&'a mut Sprite>,
+    Option<&'a mut TextureAtlasSprite>,
+    Option<&'a mut Handle<TextureAtlas>>,
+);
+
+/// World area the camera shows.
+pub fn visible_rect(transform: &Transform, projection: &OrthographicProjection) -> Rect {
+    Rect::from_center_size(
+        transform.translation.truncate() + projection.area.center(),
+        projection.area.size(),
+    )
+}
+
+/// Offsets of copies of a position across edges that would be on screen.
+pub fn ghost_offsets(playfield: &Playfield, view: Rect, pos: Vec2) -> Vec<Vec2> {
+    let view = view.inset(GHOST_MARGIN);
+    GHOST_AXES
+        .iter()
+        .map(|axes| ghost_offset(playfield, view, pos, *axes))
+        .filter(|offset| view.contains(pos + *offset))
+        .collect()
+}
+
+/// Offset to the copy across the edges on `axes` nearest the camera.
+fn ghost_offset(playfield: &Playfield, view: Rect, pos: Vec2, axes: BVec2) -> Vec2 {
+    let toward_view = (view.center() - pos).signum();
+    Vec2::select(axes, toward_view * playfield.size, Vec2::ZERO)
+}
+
+/// Sprites that just started wrapping around the world.
+type NewSprite = (Added<Position>, With<Sprite>);
+
+/// Give sprites that wrap around the world their ghosts.
+pub fn spawn_ghosts(
+    mut commands: Commands,
+    sprite_query: Query<(Entity, &Handle<Image>), NewSprite>,
+    atlas_query: Query<(Entity, &Handle<TextureAtlas>), Added<Position>>,
+) {
+    for (source, texture) in &sprite_query {
+        for axes in GHOST_AXES {
+            commands.spawn((
+                SpriteBundle {
+                    texture: texture.clone(),
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+                Ghost { source, axes },
+            ));
+        }
+    }
+    for (source, atlas) in &atlas_query {
+        for axes in GHOST_AXES {
+            commands.spawn((
+                SpriteSheetBundle {
+                    texture_atlas: atlas.clone(),
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+                Ghost { source, axes },
+            ));
+        }
+    }
+}
+
+/// The followed camera, kept apart from ghost transforms.
+type FocusCamera = (With<CameraFocus>, Without<Ghost>);
+
+/// Mirror each ghost's source and show it while its copy is on screen.
+pub fn update_ghosts(
+    mut commands: Commands,
+    playfield: Res<Playfield>,
+    camera_query: Query<(&Transform, &OrthographicProjection), FocusCamera>,
+    source_query: Query<SourceSprite, Without<Ghost>>,
+    mut ghost_query: Query<GhostSprite>,
+) {
+    let Ok((camera_transform, projection)) = camera_query.get_single() else {
+        return;
+    };
+    let view = visible_rect(camera_transform, projection).inset(GHOST_MARGIN);
+
+    for (entity, ghost, mut transform, mut visibility, sprite, atlas_sprite, atlas) in
+        &mut ghost_query
+    {
+        let Ok((
+            source_transform,
+            source_visibility,
+            source_sprite,
+            source_atlas_sprite,
+            source_atlas,
+        )) = source_query.get(ghost.source)
+        else {
+            // Source despawned.
+            commands.entity(entity).despawn();
+            continue;
+        };
+        let pos = source_transform.translation.truncate();
+        let offset = ghost_offset(&playfield, view, pos, ghost.axes);
+        if !view.contains(pos + offset) {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        }
+
+        *transform = *source_transform;
+        transform.translation += offset.extend(0.0);
+        // Follow the source being hidden by the theme.
+        visibility.set_if_neq(*source_visibility);
+        if let (Some(mut sprite), Some(source_sprite)) = (sprite, source_sprite) {
+            *sprite = source_sprite.clone();
+        }
+        if let (Some(mut atlas_sprite), Some(source_atlas_sprite)) =
+            (atlas_sprite, source_atlas_sprite)
+        {
+            *atlas_sprite = source_atlas_sprite.clone();
+        }
+        if let (Some(mut atlas), Some(source_atlas)) = (atlas, source_atlas) {
+            if *atlas != *source_atlas {
+                *atlas = source_atlas.clone();
+            }
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+
+use bevy::{
+    input::gamepad::{GamepadConnection, GamepadConnectionEvent},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use super::settings::{load_ron, save_ron};
+
+const BINDINGS_PATH: &str = "bindings.ron";
+/// Number of keys that can be bound to an action from the controls screen.
+pub const MAX_BINDINGS: usize = 2;
+/// Stick deflection ignored to avoid drift.
+const GAMEPAD_DEADZONE: f32 = 0.15;
+/// Stick deflection treated as a digital press.
+const GAMEPAD_PRESS_THRESHOLD: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize)]
+pub enum Action {
+    RotateLeft,
+    RotateRight,
+    Thrust,
+    Fire,
+    Hyperspace,
+    Pause,
+}
+
+impl Action {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::RotateLeft => "Rotate Left",
+            Action::RotateRight => "Rotate Right",
+            Action::Thrust => "Thrust",
+            Action::Fire => "Fire",
+            Action::Hyperspace => "Hyperspace",
+            Action::Pause => "Pause",
+        }
+    }
+
+    /// Gamepad buttons mapped to the action.
+    pub fn gamepad_buttons(&self) -> &'static [GamepadButtonType] {
+        match self {
+            Action::RotateLeft => &[GamepadButtonType::DPadLeft],
+            Action::RotateRight => &[GamepadButtonType::DPadRight],
+            Action::Thrust => &[
+                GamepadButtonType::RightTrigger2,
+                GamepadButtonType::RightTrigger,
+            ],
+            Action::Fire => &[GamepadButtonType::South, GamepadButtonType::West],
+            Action::Hyperspace => &[GamepadButtonType::East, GamepadButtonType::North],
+            Action::Pause => &[GamepadButtonType::Start],
+        }
+    }
+}
+
+/// Analog rotation from -1.0 (left) to 1.0 (right).
+#[derive(Debug, Resource, Default, Deref)]
+pub struct RotationAxis(pub f32);
+
+/// Gamepad used for input. Replaced on connection changes.
+#[derive(Debug, Resource, Default)]
+pub struct ActiveGamepad(pub Option<Gamepad>);
+
+/// Rescale stick deflection past the deadzone to the range 0.0 to 1.0.
+fn apply_deadzone(value: f32) -> f32 {
+    if value.abs() < GAMEPAD_DEADZONE {
+        0.0
+    } else {
+        value.signum() * (value.abs() - GAMEPAD_DEADZONE) / (1.0 - GAMEPAD_DEADZONE)
+    }
+}
+
+/// Keys bound to each [`Action`].
+#[derive(Debug, Resource, Clone, PartialEq, Deref, DerefMut, Serialize, Deserialize)]
+pub struct KeyBindings(pub HashMap<Action, Vec<KeyCode>>);
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self(HashMap::from([
+            (Action::RotateLeft, vec![KeyCode::Left, KeyCode::A]),
+            (Action::RotateRight, vec![KeyCode::Right, KeyCode::D]),
+            (Action::Thrust, vec![KeyCode::Up, KeyCode::W]),
+            (Action::Fire, vec![KeyCode::Space]),
+            (Action::Hyperspace, vec![KeyCode::ShiftLeft]),
+            (Action::Pause, vec![KeyCode::Escape]),
+        ]))
+    }
+}
+
+impl KeyBindings {
+    pub fn load() -> Result<Self, String> {
+        let mut bindings: Self = load_ron(BINDINGS_PATH)?;
+        // Actions added since bindings were saved use their defaults.
+        for (action, keys) in KeyBindings::default().0 {
+            bindings.entry(action).or_insert(keys);
+        }
+        Ok(bindings)
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        save_ron(BINDINGS_PATH, self)
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.get(&action).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Find the action and binding slot a key is bound to.
+    pub fn find(&self, key: KeyCode) -> Option<(Action, usize)> {
+        Action::iter().find_map(|action| {
+            self.keys(action)
+                .iter()
+                .position(|bound_key| *bound_key == key)
+                .map(|idx| (action, idx))
+        })
+    }
+
+    /// Bind a key to an action's slot, replacing any key there.
+    /// Keys are kept packed from the first slot, so binding past the last bound key
+    /// fills the first empty slot instead.
+    pub fn bind(&mut self, action: Action, slot: usize, key: KeyCode) {
+        let keys = self.entry(action).or_default();
+        let slot = slot.min(keys.len());
+        if slot == keys.len() {
+            keys.push(key);
+        } else {
+            keys[slot] = key;
+        }
+    }
+
+    /// Clear an action's slot. Keys in later slots move up to fill it.
+    pub fn unbind(&mut self, action: Action, slot: usize) {
+        if let Some(keys) = self.get_mut(&action) {
+            if slot < keys.len() {
+                keys.remove(slot);
+            }
+        }
+    }
+}
+
+/// Track the gamepad to read input from as gamepads are plugged in and out.
+pub fn update_active_gamepad(
+    mut connection_events: EventReader<GamepadConnectionEvent>,
+    gamepads: Res<Gamepads>,
+    mut active_gamepad: ResMut<ActiveGamepad>,
+) {
+    for event in connection_events.read() {
+        match &event.connection {
+            GamepadConnection::Connected(info) => {
+                info!("Gamepad {} connected: {}", event.gamepad.id, info.name);
+                active_gamepad.0.get_or_insert(event.gamepad);
+            }
+            GamepadConnection::Disconnected => {
+                info!("Gamepad {} disconnected", event.gamepad.id);
+                // Fallback to any other connected gamepad.
+                if active_gamepad.0 == Some(event.gamepad) {
+                    active_gamepad.0 = gamepads.iter().find(|gamepad| *gamepad != event.gamepad);
+                }
+            }
+        }
+    }
+}
+
+/// Map pressed keys and gamepad buttons to actions.
+pub fn update_actions(
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    bindings: Res<KeyBindings>,
+    active_gamepad: Res<ActiveGamepad>,
+    mut actions: ResMut<Input<Action>>,
+    mut rotation: ResMut<RotationAxis>,
+) {
+    let stick_x = active_gamepad
+        .0
+        .and_then(|gamepad| {
+            gamepad_axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
+        })
+        .map(apply_deadzone)
+        .unwrap_or_default();
+
+    actions.clear();
+    for action in Action::iter() {
+        let gamepad_pressed = active_gamepad.0.is_some_and(|gamepad| {
+            gamepad_buttons.any_pressed(
+                action
+                    .gamepad_buttons()
+                    .iter()
+                    .map(|button_type| GamepadButton::new(gamepad, *button_type)),
+            )
+        });
+        let stick_pressed = match action {
+            Action::RotateLeft => stick_x < -GAMEPAD_PRESS_THRESHOLD,
+            Action::RotateRight => stick_x > GAMEPAD_PRESS_THRESHOLD,
+            _ => false,
+        };
+        if keyboard_input.any_pressed(bindings.keys(action).iter().copied())
+            || gamepad_pressed
+            || stick_pressed
+        {
+            actions.press(action);
+        } else {
+            actions.release(action);
+        }
+    }
+
+    // Analog stick takes precedence over digital rotation.
+    rotation.0 = if stick_x != 0.0 {
+        stick_x
+    } else {
+        let left = actions.pressed(Action::RotateLeft) as i8;
+        let right = actions.pressed(Action::RotateRight) as i8;
+        f32::from(right - left)
+    };
+}
+
+pub fn save_bindings(bindings: Res<KeyBindings>) {
+    if let Err(err) = bindings.save() {
+        error!("Failed to save key bindings to {BINDINGS_PATH}: {err}");
+    }
+}
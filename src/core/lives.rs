@@ -0,0 +1,69 @@
+use bevy::prelude::*;
+
+use super::{player::Player, position::Position, vector::Outline, velocity::Velocity};
+
+pub const START_LIVES: u32 = 3;
+/// Time after respawning that asteroids pass through the ship.
+const RESPAWN_INVULNERABILITY_SECS: f32 = 2.0;
+/// Blinks per second while invulnerable.
+const INVULNERABLE_BLINK_RATE: f32 = 8.0;
+
+/// Ships left, including the one in play.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct Lives(pub u32);
+
+/// Ship can't collide with asteroids until the timer finishes.
+#[derive(Component)]
+pub struct Invulnerable(Timer);
+
+impl Default for Invulnerable {
+    fn default() -> Self {
+        Self(Timer::from_seconds(
+            RESPAWN_INVULNERABILITY_SECS,
+            TimerMode::Once,
+        ))
+    }
+}
+
+pub fn reset_lives(mut commands: Commands) {
+    commands.insert_resource(Lives(START_LIVES));
+}
+
+/// Move a ship that lost a life back to the center, stopped and briefly invulnerable.
+pub fn respawn_ship(
+    commands: &mut Commands,
+    ship: Entity,
+    pos: &mut Position,
+    velocity: &mut Velocity,
+) {
+    *pos = Position::default();
+    velocity.0 = Vec2::ZERO;
+    commands.entity(ship).insert(Invulnerable::default());
+}
+
+/// Blink the ship while invulnerable and make it solid again after.
+pub fn update_invulnerability(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut ship_query: Query<
+        (
+            Entity,
+            &mut Invulnerable,
+            &mut TextureAtlasSprite,
+            &mut Outline,
+        ),
+        With<Player>,
+    >,
+) {
+    for (entity, mut invulnerable, mut sprite, mut outline) in &mut ship_query {
+        let finished = invulnerable.0.tick(time.delta()).finished();
+        let blink_on =
+            ((invulnerable.0.elapsed_secs() * INVULNERABLE_BLINK_RATE) as u32).is_multiple_of(2);
+        let alpha = if finished || blink_on { 1.0 } else { 0.2 };
+        sprite.color.set_a(alpha);
+        outline.color.set_a(alpha);
+        if finished {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+    }
+}
@@ -1,27 +1,73 @@
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemParam, input::InputSystem, prelude::*, sprite::Material2dPlugin};
 
+pub mod animation;
 pub mod asteroid;
+pub mod attract;
 pub mod audio;
+pub mod background;
 pub mod bullet;
+pub mod camera;
 pub mod collision;
+pub mod controller;
+pub mod crt;
+pub mod ghost;
+pub mod input;
+pub mod lives;
+pub mod music;
+pub mod particles;
 pub mod player;
 pub mod position;
+pub mod score;
+pub mod settings;
+pub mod vector;
 pub mod velocity;
 
-use asteroid::setup_asteroids;
+use asteroid::{
+    attract_magnetic_asteroids, check_wave_cleared, setup_asteroid_defs, setup_asteroids,
+    validate_asteroid_textures, Wave,
+};
 use bullet::remove_bullets;
-use collision::{detect_asteroid_bullet_collisions, detect_asteroid_ship_collisions};
-use player::move_player;
-use position::{
-    sync_transform_w_position, update_positions, BG_SPRITE_X, BG_SPRITE_Y, BOUNDS_MAX_X,
-    BOUNDS_MAX_Y, BOUNDS_MIN_X, BOUNDS_MIN_Y,
+use collision::{
+    damage_asteroids, detect_asteroid_bullet_collisions, detect_asteroid_ship_collisions,
+    DamageAsteroid,
 };
+use player::move_player;
+use position::{sync_transform_w_position, update_positions};
 
 use self::{
-    audio::setup_audio,
-    collision::{check_win_condition, cleanup_game_entities},
+    animation::{
+        animate_sprites, attach_animation_atlases, build_animation_atlases, setup_animations,
+        SpriteAnimations,
+    },
+    attract::{exit_attract_mode, reset_idle_timer, start_attract_mode, IdleTimer},
+    audio::{
+        fade_sounds, play_sounds, setup_audio, stop_thrust_audio, update_channel_volumes,
+        update_thrust_audio, ActiveSounds, PlaySound,
+    },
+    background::{scroll_background, setup_background, StarfieldMaterial},
+    camera::{
+        fit_ui_to_window, fit_viewport_to_window, follow_ship, handle_impacts, setup_camera,
+        shake_camera, update_hit_stop, HitStop, Impact,
+    },
+    collision::cleanup_game_entities,
+    controller::{pilot_autopilot_ships, read_input_controls, PlayerController},
+    crt::CrtPlugin,
+    ghost::{spawn_ghosts, update_ghosts},
+    input::{
+        update_actions, update_active_gamepad, Action, ActiveGamepad, KeyBindings, RotationAxis,
+    },
+    lives::{reset_lives, update_invulnerability, Lives},
+    music::{play_state_music, reset_heartbeat, setup_music, update_heartbeat},
+    particles::{emit_particles, setup_particles, update_particles, EmitParticles},
     player::setup_player,
+    score::{
+        award_points, decay_combo, reset_score, save_high_scores, update_score_popups, AwardPoints,
+        Combo, HighScores, Score,
+    },
+    settings::{apply_settings, log_startup_warnings, Settings, StartupWarnings},
+    vector::{apply_theme, draw_outlines, Theme},
 };
+use crate::ui::menu::MenuState;
 
 pub struct GamePlugin;
 
@@ -31,85 +77,213 @@ pub enum AppState {
     Menu,
     InGame,
     Paused,
+    /// Demo game played by the computer while the main menu is idle.
+    Attract,
+}
+
+/// Game simulation runs, whether played or in the demo.
+pub fn game_running(app_state: Res<State<AppState>>) -> bool {
+    matches!(app_state.get(), AppState::InGame | AppState::Attract)
 }
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(Time::<Fixed>::from_hz(60.0))
+        let mut warnings = app
+            .world
+            .get_resource_or_insert_with(StartupWarnings::default);
+        let bindings = warnings.or_default(KeyBindings::load());
+        let high_scores = warnings.or_default(HighScores::load());
+
+        app.add_plugins((CrtPlugin, Material2dPlugin::<StarfieldMaterial>::default()))
+            .insert_resource(Time::<Fixed>::from_hz(60.0))
             .add_state::<AppState>()
-            .add_systems(Startup, setup_audio)
-            .add_systems(Startup, (setup_camera, setup_background))
+            .insert_resource(bindings)
+            .insert_resource(high_scores)
+            .add_systems(Startup, log_startup_warnings)
+            .init_resource::<Score>()
+            .init_resource::<Combo>()
+            .init_resource::<Lives>()
+            .init_resource::<Wave>()
+            .init_resource::<Input<Action>>()
+            .init_resource::<RotationAxis>()
+            .init_resource::<ActiveGamepad>()
+            .add_systems(
+                PreUpdate,
+                (update_active_gamepad, update_actions)
+                    .chain()
+                    .after(InputSystem),
+            )
+            .insert_resource(PlayerController::from_args())
+            .add_systems(
+                PreUpdate,
+                read_input_controls
+                    .after(update_actions)
+                    .run_if(game_running),
+            )
+            .init_resource::<IdleTimer>()
+            .add_systems(
+                Update,
+                (
+                    start_attract_mode
+                        .run_if(in_state(AppState::Menu).and_then(in_state(MenuState::Main))),
+                    exit_attract_mode.run_if(in_state(AppState::Attract)),
+                ),
+            )
+            .add_systems(OnEnter(MenuState::Main), reset_idle_timer)
+            .add_systems(
+                OnEnter(AppState::Attract),
+                (setup_player, reset_lives, setup_asteroids, reset_score),
+            )
+            .add_systems(OnExit(AppState::Attract), cleanup_game_entities)
+            .add_event::<PlaySound>()
+            .init_resource::<ActiveSounds>()
+            .add_systems(Startup, (setup_audio, setup_music))
+            .add_systems(
+                Update,
+                (
+                    play_sounds,
+                    play_state_music.run_if(state_changed::<AppState>()),
+                    update_channel_volumes.run_if(
+                        resource_changed::<Settings>().or_else(state_changed::<AppState>()),
+                    ),
+                    fade_sounds,
+                    (update_heartbeat, update_thrust_audio).run_if(in_state(AppState::InGame)),
+                ),
+            )
+            .add_systems(OnExit(AppState::InGame), stop_thrust_audio)
+            .add_systems(Startup, (setup_camera, setup_background, setup_particles))
+            .init_resource::<SpriteAnimations>()
+            .add_systems(Startup, (setup_animations, setup_asteroid_defs))
+            .add_systems(Update, validate_asteroid_textures)
+            .add_systems(
+                Update,
+                (
+                    apply_theme,
+                    draw_outlines.run_if(|settings: Res<Settings>| settings.theme == Theme::Vector),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    build_animation_atlases,
+                    attach_animation_atlases,
+                    animate_sprites,
+                )
+                    .chain(),
+            )
             .add_systems(
                 OnTransition {
                     from: AppState::Menu,
                     to: AppState::InGame,
                 },
-                (setup_player, setup_asteroids),
+                (
+                    setup_player,
+                    reset_lives,
+                    setup_asteroids,
+                    reset_heartbeat,
+                    reset_score,
+                ),
             )
             .add_systems(
                 FixedUpdate,
-                (update_positions, sync_transform_w_position).run_if(in_state(AppState::InGame)),
+                (update_positions, sync_transform_w_position).run_if(game_running),
             )
             .add_systems(
                 FixedUpdate,
                 (
-                    move_player,
+                    (pilot_autopilot_ships, move_player).chain(),
                     remove_bullets,
                     detect_asteroid_ship_collisions,
-                    detect_asteroid_bullet_collisions,
-                    check_win_condition,
+                    update_invulnerability,
+                    (
+                        detect_asteroid_bullet_collisions,
+                        damage_asteroids,
+                        award_points,
+                    )
+                        .chain(),
+                    (decay_combo, update_score_popups),
+                    check_wave_cleared,
+                    attract_magnetic_asteroids,
+                    scroll_background,
                 )
-                    .run_if(in_state(AppState::InGame)),
+                    .run_if(game_running),
+            )
+            .add_event::<DamageAsteroid>()
+            .add_event::<AwardPoints>()
+            .add_event::<EmitParticles>()
+            .add_event::<Impact>()
+            .init_resource::<HitStop>()
+            .add_systems(
+                Update,
+                (
+                    (
+                        handle_impacts,
+                        update_hit_stop,
+                        follow_ship,
+                        shake_camera,
+                        spawn_ghosts,
+                        update_ghosts,
+                    )
+                        .chain(),
+                    fit_ui_to_window,
+                    fit_viewport_to_window,
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                // Keep effects from the final hit moving behind the menu.
+                (emit_particles, update_particles)
+                    .chain()
+                    .after(move_player)
+                    .after(detect_asteroid_ship_collisions)
+                    .after(damage_asteroids)
+                    .run_if(not(in_state(AppState::Paused))),
+            )
+            .add_systems(
+                // Every frame, as fixed steps can miss a press.
+                // Don't toggle pause while navigating other menus.
+                Update,
+                pause_continue_game
+                    .run_if(in_state(MenuState::Disabled).or_else(in_state(MenuState::Pause))),
+            )
+            .add_systems(
+                Update,
+                apply_settings.run_if(resource_changed::<Settings>()),
             )
-            .add_systems(FixedUpdate, pause_continue_game)
             .add_systems(
                 OnTransition {
                     from: AppState::InGame,
                     to: AppState::Menu,
                 },
-                cleanup_game_entities,
+                (cleanup_game_entities, save_high_scores),
             );
     }
 }
 
-fn setup_camera(mut commands: Commands) {
-    // 2D orthographic camera
-    commands.spawn(Camera2dBundle::default());
+/// Game and menu states, which change together.
+#[derive(SystemParam)]
+pub struct NextStates<'w> {
+    game: ResMut<'w, NextState<AppState>>,
+    menu: ResMut<'w, NextState<MenuState>>,
 }
 
-fn setup_background(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let space_bg_handle = asset_server.load("embedded://Textures/tb_space.png");
-
-    // Spawn background texture across entire screen bounds.
-    let mut x_bg_pos = BOUNDS_MIN_X - BG_SPRITE_X;
-    let x_max_bg_pos = BOUNDS_MAX_X + BG_SPRITE_X;
-    let y_bg_pos = BOUNDS_MIN_Y - BG_SPRITE_Y;
-    let y_max_bg_pos = BOUNDS_MAX_Y + BG_SPRITE_Y;
-    // Floating point step range. Rust doesn't allow.
-    while x_bg_pos < x_max_bg_pos {
-        let mut y_bg_pos_new_row = y_bg_pos;
-        while y_bg_pos_new_row < y_max_bg_pos {
-            commands.spawn(SpriteBundle {
-                texture: space_bg_handle.clone(),
-                transform: Transform::from_translation(Vec3::new(x_bg_pos, y_bg_pos_new_row, 0.0)),
-                ..default()
-            });
-            y_bg_pos_new_row += BG_SPRITE_Y
-        }
-        x_bg_pos += BG_SPRITE_X;
+impl NextStates<'_> {
+    pub fn set(&mut self, game: AppState, menu: MenuState) {
+        self.game.set(game);
+        self.menu.set(menu);
     }
 }
 
 pub fn pause_continue_game(
-    keyboard_input: Res<Input<KeyCode>>,
+    actions: Res<Input<Action>>,
     app_state: Res<State<AppState>>,
-    mut game_state: ResMut<NextState<AppState>>,
+    mut next_states: NextStates,
 ) {
-    if keyboard_input.just_pressed(KeyCode::Escape) {
+    if actions.just_pressed(Action::Pause) {
         match *app_state.get() {
-            AppState::InGame => game_state.set(AppState::Paused),
-            AppState::Paused => game_state.set(AppState::InGame),
-            _ => unreachable!(),
+            AppState::InGame => next_states.set(AppState::Paused, MenuState::Pause),
+            AppState::Paused => next_states.set(AppState::InGame, MenuState::Disabled),
+            AppState::Menu | AppState::Attract => {}
         }
     }
 }
@@ -0,0 +1,179 @@
+use std::time::Duration;
+
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    prelude::*,
+};
+
+use super::{
+    asteroid::{Asteroid, AsteroidSpawner, Health},
+    audio::{channel_volume, AudioChannel, BGMAudio, Fade},
+    settings::Settings,
+    AppState,
+};
+
+/// Seconds between heartbeat notes with a full and nearly cleared field.
+const HEARTBEAT_MAX_INTERVAL: f32 = 1.0;
+const HEARTBEAT_MIN_INTERVAL: f32 = 0.25;
+const HEARTBEAT_NOTE_DURATION: Duration = Duration::from_millis(120);
+const HEARTBEAT_FREQUENCIES: [f32; 2] = [110.0, 98.0];
+
+/// Playback speeds giving each state its own take on the theme.
+const MENU_MUSIC_SPEED: f32 = 0.85;
+const IN_GAME_MUSIC_SPEED: f32 = 1.0;
+const PAUSED_MUSIC_SPEED: f32 = 0.7;
+
+/// A source and the speed it's played at.
+#[derive(Component, Clone, PartialEq)]
+pub struct MusicTrack {
+    pub source: Handle<AudioSource>,
+    pub speed: f32,
+}
+
+/// Music played in each app state. States sharing a track keep playing it.
+#[derive(Resource)]
+pub struct MusicTracks {
+    pub menu: MusicTrack,
+    pub in_game: MusicTrack,
+    pub paused: MusicTrack,
+}
+
+impl MusicTracks {
+    fn get(&self, state: &AppState) -> &MusicTrack {
+        match state {
+            AppState::Menu | AppState::Attract => &self.menu,
+            AppState::InGame => &self.in_game,
+            AppState::Paused => &self.paused,
+        }
+    }
+}
+
+/// Alternating low notes that speed up as the field clears, like the arcade original.
+#[derive(Resource)]
+pub struct Heartbeat {
+    notes: [Handle<Pitch>; 2],
+    next_note: usize,
+    timer: Timer,
+    /// Most hits needed to clear the field this game.
+    peak_hits: u32,
+}
+
+pub fn setup_music(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut pitches: ResMut<Assets<Pitch>>,
+) {
+    let theme = asset_server.load("embedded://Files/HoliznaCC0_GameBOI1.mp3");
+    let track = |speed| MusicTrack {
+        source: theme.clone(),
+        speed,
+    };
+    commands.insert_resource(MusicTracks {
+        menu: track(MENU_MUSIC_SPEED),
+        in_game: track(IN_GAME_MUSIC_SPEED),
+        paused: track(PAUSED_MUSIC_SPEED),
+    });
+    commands.insert_resource(Heartbeat {
+        notes: HEARTBEAT_FREQUENCIES
+            .map(|frequency| pitches.add(Pitch::new(frequency, HEARTBEAT_NOTE_DURATION))),
+        next_note: 0,
+        timer: Timer::from_seconds(HEARTBEAT_MAX_INTERVAL, TimerMode::Repeating),
+        peak_hits: 0,
+    });
+}
+
+/// Crossfade to the current state's track. The track restarts when a game ends.
+pub fn play_state_music(
+    mut commands: Commands,
+    app_state: Res<State<AppState>>,
+    mut prev_state: Local<Option<AppState>>,
+    tracks: Res<MusicTracks>,
+    mut music_query: Query<(&MusicTrack, &mut Fade), With<BGMAudio>>,
+) {
+    let state = *app_state.get();
+    let game_ended = matches!(
+        (*prev_state, state),
+        (Some(AppState::InGame | AppState::Paused), AppState::Menu)
+    );
+    *prev_state = Some(state);
+
+    let track = tracks.get(&state);
+    let already_playing = music_query
+        .iter()
+        .any(|(playing, fade)| playing == track && fade.target > 0.0);
+    if already_playing && !game_ended {
+        return;
+    }
+
+    for (_, mut fade) in &mut music_query {
+        fade.target = 0.0;
+    }
+    // Start silent and let the fade set the volume.
+    commands.spawn((
+        AudioBundle {
+            source: track.source.clone(),
+            settings: PlaybackSettings {
+                mode: PlaybackMode::Loop,
+                volume: Volume::new_relative(0.0),
+                speed: track.speed,
+                ..default()
+            },
+        },
+        track.clone(),
+        AudioChannel::Music,
+        Fade::fade_in(),
+        BGMAudio,
+    ));
+}
+
+pub fn reset_heartbeat(mut heartbeat: ResMut<Heartbeat>) {
+    heartbeat.peak_hits = 0;
+    heartbeat.next_note = 0;
+    heartbeat.timer = Timer::from_seconds(HEARTBEAT_MAX_INTERVAL, TimerMode::Repeating);
+}
+
+/// Play the heartbeat faster as fewer asteroids remain.
+pub fn update_heartbeat(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    app_state: Res<State<AppState>>,
+    mut heartbeat: ResMut<Heartbeat>,
+    spawner: AsteroidSpawner,
+    asteroid_query: Query<(&Asteroid, &Health)>,
+) {
+    let hits: u32 = asteroid_query
+        .iter()
+        .map(|(asteroid, health)| {
+            let material = &spawner.materials[&asteroid.material];
+            spawner
+                .defs
+                .hits_to_clear(asteroid.size, material, health.0)
+        })
+        .sum();
+    heartbeat.peak_hits = heartbeat.peak_hits.max(hits);
+    if heartbeat.peak_hits == 0 {
+        return;
+    }
+
+    let cleared = 1.0 - hits as f32 / heartbeat.peak_hits as f32;
+    let interval =
+        HEARTBEAT_MAX_INTERVAL + (HEARTBEAT_MIN_INTERVAL - HEARTBEAT_MAX_INTERVAL) * cleared;
+    heartbeat
+        .timer
+        .set_duration(Duration::from_secs_f32(interval));
+    if !heartbeat.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let note = heartbeat.notes[heartbeat.next_note].clone();
+    heartbeat.next_note = (heartbeat.next_note + 1) % heartbeat.notes.len();
+    let volume = channel_volume(&settings, AudioChannel::Music, app_state.get());
+    commands.spawn((
+        PitchBundle {
+            source: note,
+            settings: PlaybackSettings::DESPAWN.with_volume(Volume::new_relative(volume)),
+        },
+        AudioChannel::Music,
+    ));
+}
@@ -0,0 +1,200 @@
+use std::ops::Range;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::position::Playfield;
+
+/// Particles spawned up front and reused. Emitting past this skips particles.
+const PARTICLE_POOL_SIZE: usize = 512;
+/// Keeps particles over asteroids and the ship.
+const PARTICLE_Z: f32 = 2.0;
+
+/// Effects made of particles.
+#[derive(Debug, Clone, Copy)]
+pub enum ParticleEffect {
+    /// Rock fragments from a destroyed asteroid of a radius. Bigger asteroids emit more, faster debris.
+    Debris(f32),
+    ShipExplosion,
+    /// Fireball from an explosive asteroid with a blast radius.
+    Blast(f32),
+    /// Flame behind the thrusting ship.
+    Exhaust,
+}
+
+/// How an effect's particles are spawned.
+struct Emitter {
+    count: usize,
+    speed: Range<f32>,
+    lifetime: Range<f32>,
+    size: f32,
+    color: Color,
+    /// Angle in radians particles spread around the emitted direction.
+    spread: f32,
+}
+
+impl ParticleEffect {
+    fn emitter(&self) -> Emitter {
+        match self {
+            ParticleEffect::Debris(radius) => {
+                let scale = *radius;
+                Emitter {
+                    count: (scale / 3.0) as usize,
+                    speed: 0.5..1.0 + scale / 20.0,
+                    lifetime: 0.4..0.6 + scale / 50.0,
+                    size: 3.0,
+                    color: Color::rgb(0.6, 0.55, 0.5),
+                    spread: std::f32::consts::TAU,
+                }
+            }
+            ParticleEffect::ShipExplosion => Emitter {
+                count: 40,
+                speed: 1.0..5.0,
+                lifetime: 0.6..1.4,
+                size: 4.0,
+                color: Color::ORANGE,
+                spread: std::f32::consts::TAU,
+            },
+            ParticleEffect::Blast(radius) => Emitter {
+                count: 30,
+                // Keep the range non-empty for small blasts.
+                speed: 1.0..(radius / 30.0).max(1.5),
+                lifetime: 0.3..0.7,
+                size: 4.0,
+                color: Color::rgb(1.0, 0.45, 0.2),
+                spread: std::f32::consts::TAU,
+            },
+            ParticleEffect::Exhaust => Emitter {
+                count: 1,
+                speed: 1.5..3.0,
+                lifetime: 0.15..0.35,
+                size: 3.0,
+                color: Color::rgb(1.0, 0.6, 0.2),
+                spread: 0.5,
+            },
+        }
+    }
+}
+
+/// Request to emit an effect's particles.
+#[derive(Event)]
+pub struct EmitParticles {
+    pub effect: ParticleEffect,
+    pub position: Vec2,
+    /// Direction particles spread around.
+    pub direction: Vec2,
+}
+
+impl EmitParticles {
+    /// Emit particles in every direction.
+    pub fn burst(effect: ParticleEffect, position: Vec2) -> Self {
+        Self {
+            effect,
+            position,
+            direction: Vec2::X,
+        }
+    }
+
+    pub fn directed(effect: ParticleEffect, position: Vec2, direction: Vec2) -> Self {
+        Self {
+            effect,
+            position,
+            direction,
+        }
+    }
+}
+
+#[derive(Component, Default)]
+pub struct Particle {
+    velocity: Vec2,
+    color: Color,
+    lifetime: Timer,
+}
+
+/// Hidden particles free to emit.
+#[derive(Resource, Default)]
+pub struct ParticlePool(Vec<Entity>);
+
+pub fn setup_particles(mut commands: Commands) {
+    let particles = (0..PARTICLE_POOL_SIZE)
+        .map(|_| {
+            commands
+                .spawn((
+                    SpriteBundle {
+                        visibility: Visibility::Hidden,
+                        ..default()
+                    },
+                    Particle::default(),
+                ))
+                .id()
+        })
+        .collect();
+    commands.insert_resource(ParticlePool(particles));
+}
+
+/// Take particles from the pool for requested effects.
+pub fn emit_particles(
+    mut emit_events: EventReader<EmitParticles>,
+    mut pool: ResMut<ParticlePool>,
+    mut particle_query: Query<(&mut Particle, &mut Transform, &mut Sprite, &mut Visibility)>,
+) {
+    let mut rng = rand::thread_rng();
+    for event in emit_events.read() {
+        let emitter = event.effect.emitter();
+        let base_angle = event.direction.y.atan2(event.direction.x);
+        for _ in 0..emitter.count {
+            let Some(entity) = pool.0.pop() else {
+                break;
+            };
+            let Ok((mut particle, mut transform, mut sprite, mut visibility)) =
+                particle_query.get_mut(entity)
+            else {
+                continue;
+            };
+            let angle = base_angle + emitter.spread * rng.gen_range(-0.5..=0.5);
+            particle.velocity = Vec2::from_angle(angle) * rng.gen_range(emitter.speed.clone());
+            particle.color = emitter.color;
+            particle.lifetime =
+                Timer::from_seconds(rng.gen_range(emitter.lifetime.clone()), TimerMode::Once);
+            sprite.color = emitter.color;
+            sprite.custom_size = Some(Vec2::splat(emitter.size));
+            transform.translation = event.position.extend(PARTICLE_Z);
+            *visibility = Visibility::Visible;
+        }
+    }
+}
+
+/// Move and fade out particles, returning expired ones to the pool.
+pub fn update_particles(
+    time: Res<Time>,
+    playfield: Res<Playfield>,
+    mut pool: ResMut<ParticlePool>,
+    mut particle_query: Query<(
+        Entity,
+        &mut Particle,
+        &mut Transform,
+        &mut Sprite,
+        &mut Visibility,
+    )>,
+) {
+    for (entity, mut particle, mut transform, mut sprite, mut visibility) in &mut particle_query {
+        if *visibility == Visibility::Hidden {
+            continue;
+        }
+        if particle.lifetime.tick(time.delta()).finished() {
+            *visibility = Visibility::Hidden;
+            pool.0.push(entity);
+            continue;
+        }
+        let half_size = sprite
+            .custom_size
+            .map_or(0.0, |size| size.max_element() / 2.0);
+        transform.translation = playfield
+            .wrap(
+                transform.translation.truncate() + particle.velocity,
+                half_size,
+            )
+            .extend(PARTICLE_Z);
+        sprite.color = particle.color.with_a(particle.lifetime.percent_left());
+    }
+}
@@ -3,55 +3,81 @@ use std::f32::consts::PI;
 use bevy::prelude::*;
 
 use super::{
-    audio::BulletFiredAudio,
-    bullet::{Bullet, BulletImage, BULLET_VELOCITY},
-    position::Position,
+    animation::{AnimationId, LoopMode, SpriteAnimation, SpriteAnimations},
+    audio::{PlaySound, SoundEvent},
+    bullet::{Bullet, BULLET_VELOCITY},
+    controller::{PlayerController, ShipController, ShipControls},
+    particles::{EmitParticles, ParticleEffect},
+    position::{Playfield, Position},
+    vector::Outline,
     velocity::Velocity,
+    AppState,
 };
 
 const SHIP_ROTATION_SPEED: f32 = 10.0 * PI / 360.0;
 const SHIP_ACCELERATION: f32 = 0.2;
 const SHIP_DECELERATION: f32 = 0.01;
 const SHIP_MAX_VELOCITY: f32 = 10.0;
+/// Shortest time between shots.
+const SHIP_FIRE_INTERVAL: f32 = 0.1;
+/// Frame rate of the ship and bullet animations.
+const SPRITE_FPS: f32 = 10.0;
+/// Distance behind the ship's center that exhaust is emitted from.
+const SHIP_EXHAUST_OFFSET: f32 = 14.0;
 
 /// player component
 #[derive(Default, Component)]
 pub struct Player {
     pub rotation_angle: f32,
-    pub score: usize,
+    /// Seconds until the ship can fire again.
+    fire_cooldown: f32,
 }
 
 impl Player {
-    fn direction(&self) -> Vec2 {
+    pub fn direction(&self) -> Vec2 {
         // https://en.wikipedia.org/wiki/Trigonometric_functions
         let (y, x) = (self.rotation_angle + (PI / 2.0)).sin_cos();
         Vec2::new(x, y)
     }
+
+    pub fn can_fire(&self) -> bool {
+        self.fire_cooldown <= 0.0
+    }
 }
 
 pub fn move_player(
-    keyboard_input: Res<Input<KeyCode>>,
-    bullet_image: Res<BulletImage>,
-    bullet_fired_audio: Res<BulletFiredAudio>,
+    time: Res<Time>,
+    animations: Res<SpriteAnimations>,
+    mut sound_events: EventWriter<PlaySound>,
+    mut particle_events: EventWriter<EmitParticles>,
+    playfield: Res<Playfield>,
     mut commands: Commands,
-    mut query: Query<(&mut Player, &mut Position, &mut Velocity, &mut Transform)>,
+    mut query: Query<(
+        &mut Player,
+        &mut ShipControls,
+        &mut Position,
+        &mut Velocity,
+        &mut Transform,
+    )>,
 ) {
-    let Ok((mut ship, pos, mut velocity, mut transform)) = query.get_single_mut() else {
+    let Ok((mut ship, mut controls, mut pos, mut velocity, mut transform)) = query.get_single_mut()
+    else {
         return;
     };
+    ship.fire_cooldown -= time.delta_seconds();
 
-    // Pivot ship.
-    if keyboard_input.pressed(KeyCode::Left) {
-        ship.rotation_angle += SHIP_ROTATION_SPEED;
-    }
-    if keyboard_input.pressed(KeyCode::Right) {
-        ship.rotation_angle -= SHIP_ROTATION_SPEED;
-    }
+    // Pivot ship. Analog input turns slower with less deflection.
+    ship.rotation_angle -= SHIP_ROTATION_SPEED * controls.turn.clamp(-1.0, 1.0);
     transform.rotation = Quat::from_rotation_z(ship.rotation_angle);
 
     // Accelerate and decelerate ship.
-    if keyboard_input.pressed(KeyCode::Up) {
+    if controls.thrust {
         velocity.0 += ship.direction() * SHIP_ACCELERATION;
+        particle_events.send(EmitParticles::directed(
+            ParticleEffect::Exhaust,
+            pos.0 - ship.direction() * SHIP_EXHAUST_OFFSET,
+            -ship.direction(),
+        ));
 
         // Limit ship velocity.
         if velocity.0.length() > SHIP_MAX_VELOCITY {
@@ -61,15 +87,22 @@ pub fn move_player(
         velocity.0 *= 1.0 - SHIP_DECELERATION
     }
 
-    // Add bullet.
-    if keyboard_input.just_pressed(KeyCode::Space) {
+    // Jump to random position and stop.
+    if std::mem::take(&mut controls.hyperspace) {
+        *pos = Position(playfield.random_position());
+        velocity.0 = Vec2::ZERO;
+    }
+
+    // Add bullet. Shots asked for while reloading are dropped.
+    if std::mem::take(&mut controls.fire) && ship.can_fire() {
+        ship.fire_cooldown = SHIP_FIRE_INTERVAL;
         commands.spawn((
             Bullet {
                 prev_pos: Position(**pos),
                 dst_traveled: 0.0,
             },
-            SpriteBundle {
-                texture: bullet_image.clone(),
+            SpriteSheetBundle {
+                texture_atlas: animations.atlas(AnimationId::Bullet),
                 // Spawn bullet at ship's present position.
                 transform: Transform::default()
                     .with_translation(transform.translation)
@@ -79,30 +112,40 @@ pub fn move_player(
                     ),
                 ..Default::default()
             },
+            SpriteAnimation::new(AnimationId::Bullet, SPRITE_FPS, LoopMode::Loop),
             Velocity(ship.direction().normalize() * BULLET_VELOCITY),
+            Outline::bullet(),
             Position(**pos),
         ));
-        commands.spawn(AudioBundle {
-            source: bullet_fired_audio.0.clone(),
-            settings: PlaybackSettings::DESPAWN,
-        });
+        sound_events.send(PlaySound::at(SoundEvent::BulletFired, pos.0));
     }
 }
 
-pub fn setup_player(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let ship_handle = asset_server.load("embedded://Animations/obj_player/Default/000.png");
-    let bullet_handle = asset_server.load("embedded://Animations/obj_bullet/Default/000.png");
-
+pub fn setup_player(
+    mut commands: Commands,
+    animations: Res<SpriteAnimations>,
+    app_state: Res<State<AppState>>,
+    player_controller: Res<PlayerController>,
+) {
+    // The demo always flies itself.
+    let controller = match app_state.get() {
+        AppState::Attract => ShipController::Autopilot,
+        _ => **player_controller,
+    };
     // player controlled ship
     commands.spawn((
-        SpriteBundle {
-            texture: ship_handle,
+        SpriteSheetBundle {
+            texture_atlas: animations.atlas(AnimationId::Player),
+            // Above the background.
+            transform: Transform::from_xyz(0.0, 0.0, 1.0),
             ..default()
         },
+        SpriteAnimation::new(AnimationId::Player, SPRITE_FPS, LoopMode::Loop),
         Player::default(),
+        controller,
+        ShipControls::default(),
+        Outline::ship(),
         Velocity(Vec2::default()),
         Position::default(),
     ));
-
-    commands.insert_resource(BulletImage(bullet_handle));
 }
@@ -1,46 +1,142 @@
+use std::env;
+
 use bevy::prelude::*;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
 
-use super::velocity::Velocity;
+use super::{settings::StartupWarnings, velocity::Velocity};
 use crate::make_vec2_struct_random;
 
-pub const BG_SPRITE_X: f32 = 256.0;
-pub const BG_SPRITE_Y: f32 = 256.0;
-pub const BOUNDS: Vec2 = Vec2::new(1200.0, 640.0);
-// origin is center so divide by 2.
+const PLAYFIELD_ARG: &str = "--playfield";
+const FOLLOW_ARG: &str = "--follow";
+/// Screens across and up the world when the camera follows the ship.
+const FOLLOW_WORLD_SCREENS: f32 = 3.0;
+
+/// Playfield sizes selectable at launch with `--playfield <preset>`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum PlayfieldPreset {
+    #[default]
+    Standard,
+    /// 4:3 like the arcade cabinet.
+    Arcade,
+    /// 16:9
+    Wide,
+    /// 21:9
+    Ultrawide,
+    /// Small arena for testing.
+    Tiny,
+}
+
+impl PlayfieldPreset {
+    pub fn size(&self) -> Vec2 {
+        match self {
+            PlayfieldPreset::Standard => Vec2::new(1200.0, 640.0),
+            PlayfieldPreset::Arcade => Vec2::new(960.0, 720.0),
+            PlayfieldPreset::Wide => Vec2::new(1280.0, 720.0),
+            PlayfieldPreset::Ultrawide => Vec2::new(1680.0, 720.0),
+            PlayfieldPreset::Tiny => Vec2::new(480.0, 320.0),
+        }
+    }
+
+    /// Preset named on the command line. The default if missing, and an error if unknown.
+    pub fn from_args() -> Result<Self, String> {
+        let Some(name) = env::args().skip_while(|arg| arg != PLAYFIELD_ARG).nth(1) else {
+            return Ok(Self::default());
+        };
+        Self::iter()
+            .find(|preset| format!("{preset:?}").eq_ignore_ascii_case(&name))
+            .ok_or_else(|| format!("Unknown playfield {name}, using {:?}", Self::default()))
+    }
+}
+
+/// Size of the area objects move in, centered on the origin.
 // -------
 // |  |  |
 // |--+--|
 // |  |  |
 // -------
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct Playfield {
+    pub size: Vec2,
+    /// Area shown on screen. Smaller than the world if the camera follows the ship.
+    pub view: Vec2,
+}
 
-pub const BOUNDS_MAX_X: f32 = BOUNDS.x / 2.0;
-pub const BOUNDS_MIN_X: f32 = -BOUNDS_MAX_X;
-pub const BOUNDS_MAX_Y: f32 = BOUNDS.y / 2.0;
-pub const BOUNDS_MIN_Y: f32 = -BOUNDS_MAX_Y;
+impl Playfield {
+    pub fn new(preset: PlayfieldPreset) -> Self {
+        Self {
+            size: preset.size(),
+            view: preset.size(),
+        }
+    }
 
-#[derive(Debug, Default, Component, Deref, DerefMut, Clone)]
-pub struct Position(pub Vec2);
+    /// World several screens large with the camera following the ship.
+    pub fn follow(preset: PlayfieldPreset) -> Self {
+        Self {
+            size: preset.size() * FOLLOW_WORLD_SCREENS,
+            view: preset.size(),
+        }
+    }
 
-pub fn update_positions(mut query: Query<(&Velocity, &Transform, &mut Position)>) {
-    // Board on taurus
-    for (velocity, transform, mut position) in &mut query {
-        let mut new_pos = position.0 + velocity.0;
-        // Look at scale of element and move it when fully off-screen to avoid sudden jump.
-        let half_scale = transform.scale.max_element();
+    /// Playfield from `--playfield <preset>` and `--follow` on the command line.
+    /// An unknown preset is kept as a warning and the default used.
+    pub fn from_args(warnings: &mut StartupWarnings) -> Self {
+        let preset = warnings.or_default(PlayfieldPreset::from_args());
+        if env::args().any(|arg| arg == FOLLOW_ARG) {
+            Self::follow(preset)
+        } else {
+            Self::new(preset)
+        }
+    }
 
+    pub fn follows_ship(&self) -> bool {
+        self.size != self.view
+    }
+
+    /// Top right corner. Origin is center so half the size.
+    pub fn max(&self) -> Vec2 {
+        self.size / 2.0
+    }
+
+    pub fn min(&self) -> Vec2 {
+        -self.max()
+    }
+
+    /// Shortest offset between two points, which may cross an edge.
+    pub fn wrapped_delta(&self, delta: Vec2) -> Vec2 {
+        delta - self.size * (delta / self.size).round()
+    }
+
+    pub fn random_position(&self) -> Vec2 {
+        (Position::random().0 - 0.5) * self.size
+    }
+
+    /// Move a position fully past an edge of the playfield to the opposite side.
+    /// `half_scale` keeps elements from jumping before they are off-screen.
+    pub fn wrap(&self, mut pos: Vec2, half_scale: f32) -> Vec2 {
+        let (min, max) = (self.min() - half_scale, self.max() + half_scale);
         // If passing out of max/min ?-axis view, move it to other side.
-        if new_pos.x > BOUNDS_MAX_X + half_scale {
-            new_pos.x = BOUNDS_MIN_X - half_scale
-        } else if new_pos.x < BOUNDS_MIN_X - half_scale {
-            new_pos.x = BOUNDS_MAX_X + half_scale
+        if pos.x > max.x {
+            pos.x = min.x
+        } else if pos.x < min.x {
+            pos.x = max.x
         }
-        if new_pos.y > BOUNDS_MAX_Y + half_scale {
-            new_pos.y = BOUNDS_MIN_Y - half_scale
-        } else if new_pos.y < BOUNDS_MIN_Y - half_scale {
-            new_pos.y = BOUNDS_MAX_Y + half_scale
+        if pos.y > max.y {
+            pos.y = min.y
+        } else if pos.y < min.y {
+            pos.y = max.y
         }
+        pos
+    }
+}
+
+#[derive(Debug, Default, Component, Deref, DerefMut, Clone)]
+pub struct Position(pub Vec2);
 
-        *position = Position(new_pos);
+pub fn update_positions(playfield: Res<Playfield>, mut query: Query<(&Velocity, &mut Position)>) {
+    // Board on taurus. Wrap right at the edge as ghosts draw whatever straddles it.
+    for (velocity, mut position) in &mut query {
+        *position = Position(playfield.wrap(position.0 + velocity.0, 0.0));
     }
 }
 
@@ -0,0 +1,173 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::settings::{load_ron, save_ron};
+
+const HIGH_SCORE_PATH: &str = "highscore.ron";
+/// Scores kept in the high score table.
+const HIGH_SCORE_COUNT: usize = 5;
+/// Time after a hit for the next to raise the multiplier. The multiplier drops a step each time it runs out.
+const COMBO_WINDOW_SECS: f32 = 1.5;
+const MAX_MULTIPLIER: usize = 8;
+const POPUP_LIFETIME_SECS: f32 = 1.0;
+/// Pixels per second popups drift upward.
+const POPUP_DRIFT: f32 = 40.0;
+const POPUP_FONT_SIZE: f32 = 20.0;
+/// Keeps popups over particles.
+const POPUP_Z: f32 = 3.0;
+
+/// Points scored this game.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct Score(pub usize);
+
+/// Best scores across sessions, highest first.
+#[derive(Resource, Default, Deref, Serialize, Deserialize)]
+pub struct HighScores(Vec<usize>);
+
+impl HighScores {
+    pub fn load() -> Result<Self, String> {
+        let mut high_scores: Self = load_ron(HIGH_SCORE_PATH)?;
+        // Tidy a hand edited table.
+        high_scores.0.sort_unstable_by(|a, b| b.cmp(a));
+        high_scores.0.truncate(HIGH_SCORE_COUNT);
+        Ok(high_scores)
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        save_ron(HIGH_SCORE_PATH, self)
+    }
+
+    pub fn best(&self) -> usize {
+        self.first().copied().unwrap_or_default()
+    }
+
+    /// Add a score if it makes the table. Returns whether it did.
+    fn insert(&mut self, score: usize) -> bool {
+        let rank = self.0.partition_point(|high_score| *high_score >= score);
+        if score == 0 || rank >= HIGH_SCORE_COUNT {
+            return false;
+        }
+        self.0.insert(rank, score);
+        self.0.truncate(HIGH_SCORE_COUNT);
+        true
+    }
+}
+
+/// Multiplier built up by destroying asteroids in quick succession.
+#[derive(Resource)]
+pub struct Combo {
+    pub multiplier: usize,
+    timer: Timer,
+}
+
+impl Default for Combo {
+    fn default() -> Self {
+        // Start out of the window so the first hit doesn't raise the multiplier.
+        let mut timer = Timer::from_seconds(COMBO_WINDOW_SECS, TimerMode::Once);
+        timer.tick(timer.duration());
+        Self {
+            multiplier: 1,
+            timer,
+        }
+    }
+}
+
+/// Points for destroying something at a position, before the multiplier.
+#[derive(Event)]
+pub struct AwardPoints {
+    pub points: usize,
+    pub position: Vec2,
+}
+
+/// Floating text showing points awarded.
+#[derive(Component)]
+pub struct ScorePopup(Timer);
+
+pub fn reset_score(mut score: ResMut<Score>, mut combo: ResMut<Combo>) {
+    score.0 = 0;
+    *combo = Combo::default();
+}
+
+/// Add multiplied points to the score, raise the multiplier and show a popup.
+pub fn award_points(
+    mut commands: Commands,
+    mut award_events: EventReader<AwardPoints>,
+    mut score: ResMut<Score>,
+    mut combo: ResMut<Combo>,
+) {
+    for event in award_events.read() {
+        // First hit of a combo scores at the current multiplier. Quick hits after raise it.
+        if !combo.timer.finished() {
+            combo.multiplier = (combo.multiplier + 1).min(MAX_MULTIPLIER);
+        }
+        combo.timer.reset();
+        let points = event.points * combo.multiplier;
+        score.0 += points;
+
+        let (label, color) = if combo.multiplier > 1 {
+            (
+                format!("+{} x{}", event.points, combo.multiplier),
+                Color::GOLD,
+            )
+        } else {
+            (format!("+{}", event.points), Color::WHITE)
+        };
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    label,
+                    TextStyle {
+                        font_size: POPUP_FONT_SIZE,
+                        color,
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_translation(event.position.extend(POPUP_Z)),
+                ..default()
+            },
+            ScorePopup(Timer::from_seconds(POPUP_LIFETIME_SECS, TimerMode::Once)),
+        ));
+    }
+}
+
+/// Drop the multiplier a step each time the combo window passes without a hit.
+pub fn decay_combo(time: Res<Time>, mut combo: ResMut<Combo>) {
+    // Only mark the combo changed when the multiplier does.
+    let timer = &mut combo.bypass_change_detection().timer;
+    let window_passed = timer.tick(time.delta()).just_finished();
+    if !window_passed || combo.multiplier == 1 {
+        return;
+    }
+    combo.multiplier -= 1;
+    if combo.multiplier > 1 {
+        combo.timer.reset();
+    }
+}
+
+/// Drift popups upward and fade them out.
+pub fn update_score_popups(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut popup_query: Query<(Entity, &mut ScorePopup, &mut Transform, &mut Text)>,
+) {
+    for (entity, mut popup, mut transform, mut text) in &mut popup_query {
+        if popup.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation.y += POPUP_DRIFT * time.delta_seconds();
+        for section in &mut text.sections {
+            section.style.color.set_a(popup.0.percent_left());
+        }
+    }
+}
+
+/// Keep the final score if it makes the high score table.
+pub fn save_high_scores(score: Res<Score>, mut high_scores: ResMut<HighScores>) {
+    if !high_scores.insert(score.0) {
+        return;
+    }
+    if let Err(err) = high_scores.save() {
+        error!("Failed to save high score to {HIGH_SCORE_PATH}: {err}");
+    }
+}
@@ -0,0 +1,247 @@
+use std::fs;
+
+use bevy::{
+    prelude::*,
+    window::{PresentMode, PrimaryWindow, WindowMode, WindowResolution},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use super::{
+    asteroid::{ASTEROID_NUM, ASTEROID_VELOCITY},
+    audio::AudioChannel,
+    position::Playfield,
+    vector::Theme,
+};
+
+const SETTINGS_PATH: &str = "settings.ron";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn asteroid_num(&self) -> usize {
+        match self {
+            Difficulty::Easy => ASTEROID_NUM / 2,
+            Difficulty::Normal => ASTEROID_NUM,
+            Difficulty::Hard => ASTEROID_NUM * 3 / 2,
+        }
+    }
+
+    pub fn asteroid_velocity(&self) -> f32 {
+        match self {
+            Difficulty::Easy => ASTEROID_VELOCITY * 0.75,
+            Difficulty::Normal => ASTEROID_VELOCITY,
+            Difficulty::Hard => ASTEROID_VELOCITY * 1.5,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum WindowScale {
+    Small,
+    #[default]
+    Normal,
+    Large,
+    Larger,
+}
+
+impl WindowScale {
+    pub fn factor(&self) -> f32 {
+        match self {
+            WindowScale::Small => 0.75,
+            WindowScale::Normal => 1.0,
+            WindowScale::Large => 1.25,
+            WindowScale::Larger => 1.5,
+        }
+    }
+}
+
+/// Get the variant after `value`, wrapping around to the first.
+pub fn cycle<T: IntoEnumIterator + PartialEq + Copy>(value: T) -> T {
+    let mut variants = T::iter().skip_while(|variant| *variant != value).skip(1);
+    variants
+        .next()
+        .or_else(|| T::iter().next())
+        .unwrap_or(value)
+}
+
+/// Read a RON file. Defaults if missing, and an error if invalid.
+pub fn load_ron<T: DeserializeOwned + Default>(path: &str) -> Result<T, String> {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return Ok(T::default());
+    };
+    ron::from_str(&contents).map_err(|err| format!("Invalid file {path}, using defaults: {err}"))
+}
+
+/// Problems found before logging is setup. Logged on startup.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct StartupWarnings(pub Vec<String>);
+
+impl StartupWarnings {
+    /// Take a value, or keep its error and use the default.
+    pub fn or_default<T: Default>(&mut self, result: Result<T, String>) -> T {
+        result.unwrap_or_else(|err| {
+            self.push(err);
+            T::default()
+        })
+    }
+}
+
+pub fn log_startup_warnings(warnings: Res<StartupWarnings>) {
+    for warning in warnings.iter() {
+        warn!("{warning}");
+    }
+}
+
+pub fn save_ron<T: Serialize>(path: &str, value: &T) -> Result<(), String> {
+    let contents = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+        .map_err(|err| err.to_string())?;
+    fs::write(path, contents).map_err(|err| err.to_string())
+}
+
+/// User preferences persisted between sessions.
+#[derive(Debug, Resource, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub ui_volume: f32,
+    pub music_muted: bool,
+    pub sfx_muted: bool,
+    pub ui_muted: bool,
+    pub fullscreen: bool,
+    pub vsync: bool,
+    /// Quiet positioned sounds far from the ship.
+    pub sound_attenuation: bool,
+    pub screen_shake: bool,
+    pub theme: Theme,
+    /// Post-processing strengths from 0 (off) to 1.
+    pub bloom: f32,
+    pub scanlines: f32,
+    pub barrel_distortion: f32,
+    pub chromatic_aberration: f32,
+    pub window_scale: WindowScale,
+    pub difficulty: Difficulty,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            music_volume: 1.0,
+            sfx_volume: 1.0,
+            ui_volume: 1.0,
+            music_muted: false,
+            sfx_muted: false,
+            ui_muted: false,
+            fullscreen: false,
+            vsync: true,
+            sound_attenuation: false,
+            screen_shake: true,
+            theme: Theme::default(),
+            bloom: 0.0,
+            scanlines: 0.0,
+            barrel_distortion: 0.0,
+            chromatic_aberration: 0.0,
+            window_scale: WindowScale::default(),
+            difficulty: Difficulty::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Result<Self, String> {
+        load_ron(SETTINGS_PATH)
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        save_ron(SETTINGS_PATH, self)
+    }
+
+    pub fn window_mode(&self) -> WindowMode {
+        if self.fullscreen {
+            WindowMode::BorderlessFullscreen
+        } else {
+            WindowMode::Windowed
+        }
+    }
+
+    pub fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
+    }
+
+    pub fn resolution(&self, playfield: &Playfield) -> Vec2 {
+        playfield.view * self.window_scale.factor()
+    }
+
+    /// Primary window built from settings.
+    pub fn window(&self, playfield: &Playfield) -> Window {
+        let resolution = self.resolution(playfield);
+        Window {
+            mode: self.window_mode(),
+            present_mode: self.present_mode(),
+            resolution: WindowResolution::new(resolution.x, resolution.y),
+            ..default()
+        }
+    }
+
+    pub fn channel_muted(&self, channel: AudioChannel) -> bool {
+        match channel {
+            AudioChannel::Music => self.music_muted,
+            AudioChannel::Sfx => self.sfx_muted,
+            AudioChannel::Ui => self.ui_muted,
+        }
+    }
+
+    /// Volume of a channel relative to the master volume. Zero if muted.
+    pub fn channel_volume(&self, channel: AudioChannel) -> f32 {
+        if self.channel_muted(channel) {
+            return 0.0;
+        }
+        match channel {
+            AudioChannel::Music => self.music_volume,
+            AudioChannel::Sfx => self.sfx_volume,
+            AudioChannel::Ui => self.ui_volume,
+        }
+    }
+}
+
+/// Apply changed settings to the window, camera, and audio.
+pub fn apply_settings(
+    settings: Res<Settings>,
+    playfield: Res<Playfield>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+    mut global_volume: ResMut<GlobalVolume>,
+    mut applied_scale: Local<Option<WindowScale>>,
+) {
+    if let Ok(mut window) = window_query.get_single_mut() {
+        window.mode = settings.window_mode();
+        window.present_mode = settings.present_mode();
+        // Only resize when the scale changes so a window resized by hand stays put.
+        if applied_scale.replace(settings.window_scale) != Some(settings.window_scale) {
+            let resolution = settings.resolution(&playfield);
+            window.resolution.set(resolution.x, resolution.y);
+        }
+    }
+
+    // Only applies to new sounds. Playing sounds are updated by the mixer.
+    global_volume.volume = GlobalVolume::new(settings.master_volume).volume;
+}
+
+pub fn save_settings(settings: Res<Settings>) {
+    if let Err(err) = settings.save() {
+        error!("Failed to save settings to {SETTINGS_PATH}: {err}");
+    }
+}
@@ -0,0 +1,225 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use super::{
+    camera::CameraFocus,
+    ghost::{ghost_offsets, visible_rect},
+    position::Playfield,
+    settings::Settings,
+};
+
+const ASTEROID_OUTLINE_VERTICES: usize = 12;
+/// Range of an asteroid outline's vertex distance as a fraction of its radius.
+const ASTEROID_OUTLINE_JAGGEDNESS: (f32, f32) = (0.7, 1.1);
+/// Ship outline, nose up, like the 1979 original.
+const SHIP_OUTLINE: [Vec2; 5] = [
+    Vec2::new(0.0, 20.0),
+    Vec2::new(14.0, -16.0),
+    Vec2::new(6.0, -10.0),
+    Vec2::new(-6.0, -10.0),
+    Vec2::new(-14.0, -16.0),
+];
+const BULLET_OUTLINE: [Vec2; 2] = [Vec2::new(-6.0, 0.0), Vec2::new(6.0, 0.0)];
+
+/// How ships, bullets and asteroids are drawn.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    Sprites,
+    /// Line polygons like the original vector display.
+    Vector,
+}
+
+/// Polygon drawn in the vector theme, in local space.
+#[derive(Component, Clone)]
+pub struct Outline {
+    pub vertices: Vec<Vec2>,
+    pub closed: bool,
+    pub color: Color,
+}
+
+impl Outline {
+    pub fn ship() -> Self {
+        Self {
+            vertices: SHIP_OUTLINE.to_vec(),
+            closed: true,
+            color: Color::WHITE,
+        }
+    }
+
+    pub fn bullet() -> Self {
+        Self {
+            vertices: BULLET_OUTLINE.to_vec(),
+            closed: false,
+            color: Color::WHITE,
+        }
+    }
+
+    /// Random convex outline around a radius. Also used as the asteroid's collider.
+    pub fn jagged(radius: f32, color: Color, rng: &mut impl Rng) -> Self {
+        let step = TAU / ASTEROID_OUTLINE_VERTICES as f32;
+        let points: Vec<Vec2> = (0..ASTEROID_OUTLINE_VERTICES)
+            .map(|i| {
+                let angle = step * (i as f32 + rng.gen_range(-0.4..0.4));
+                let (min, max) = ASTEROID_OUTLINE_JAGGEDNESS;
+                Vec2::from_angle(angle) * radius * rng.gen_range(min..max)
+            })
+            .collect();
+        Self {
+            vertices: convex_hull(points),
+            closed: true,
+            color,
+        }
+    }
+
+    /// Whether a circle at an offset from the outline's origin overlaps it.
+    /// Assumes the outline is convex.
+    pub fn overlaps_circle(&self, offset: Vec2, radius: f32) -> bool {
+        let edges = self
+            .vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1));
+        let mut inside = true;
+        for (start, end) in edges {
+            let edge = *end - *start;
+            let to_point = offset - *start;
+            // Counter-clockwise vertices have the inside to the left of each edge.
+            if edge.perp_dot(to_point) < 0.0 {
+                inside = false;
+            }
+            let t = (to_point.dot(edge) / edge.length_squared()).clamp(0.0, 1.0);
+            if (*start + edge * t).distance(offset) < radius {
+                return true;
+            }
+        }
+        inside
+    }
+}
+
+/// Counter-clockwise convex hull of points using the monotone chain algorithm.
+fn convex_hull(mut points: Vec<Vec2>) -> Vec<Vec2> {
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    let turns_left = |hull: &[Vec2], point: Vec2| {
+        let [.., a, b] = hull else {
+            return true;
+        };
+        (*b - *a).perp_dot(point - *b) > 0.0
+    };
+
+    let mut hull: Vec<Vec2> = Vec::with_capacity(points.len() + 1);
+    for pass in [points.clone(), points.into_iter().rev().collect()] {
+        let start = hull.len();
+        for point in pass {
+            while hull.len() >= start + 2 && !turns_left(&hull[start..], point) {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        // Last point of each half is the first of the next.
+        hull.pop();
+    }
+    hull
+}
+
+/// Show sprites or outlines for the current theme.
+pub fn apply_theme(
+    settings: Res<Settings>,
+    mut visibility_query: Query<&mut Visibility, With<Outline>>,
+) {
+    let visibility = match settings.theme {
+        Theme::Sprites => Visibility::Inherited,
+        Theme::Vector => Visibility::Hidden,
+    };
+    for mut entity_visibility in &mut visibility_query {
+        entity_visibility.set_if_neq(visibility);
+    }
+}
+
+/// Draw outlines, plus copies across the world's edges for those near a seam.
+pub fn draw_outlines(
+    mut gizmos: Gizmos,
+    playfield: Res<Playfield>,
+    camera_query: Query<(&Transform, &OrthographicProjection), With<CameraFocus>>,
+    outline_query: Query<(&Outline, &Transform), Without<CameraFocus>>,
+) {
+    let Ok((camera_transform, projection)) = camera_query.get_single() else {
+        return;
+    };
+    let view = visible_rect(camera_transform, projection);
+    for (outline, transform) in &outline_query {
+        let mut points: Vec<Vec2> = outline
+            .vertices
+            .iter()
+            .map(|vertex| transform.transform_point(vertex.extend(0.0)).truncate())
+            .collect();
+        if let (true, Some(first)) = (outline.closed, points.first().copied()) {
+            points.push(first);
+        }
+        let ghosts = ghost_offsets(&playfield, view, transform.translation.truncate());
+        for offset in ghosts.into_iter().chain([Vec2::ZERO]) {
+            gizmos.linestrip_2d(points.iter().map(|point| *point + offset), outline.color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SQUARE: [Vec2; 4] = [
+        Vec2::new(-10.0, -10.0),
+        Vec2::new(10.0, -10.0),
+        Vec2::new(10.0, 10.0),
+        Vec2::new(-10.0, 10.0),
+    ];
+
+    fn square() -> Outline {
+        Outline {
+            vertices: SQUARE.to_vec(),
+            closed: true,
+            color: Color::WHITE,
+        }
+    }
+
+    #[test]
+    fn convex_hull_drops_collinear_points() {
+        let mut points = SQUARE.to_vec();
+        // Edge midpoints and the center.
+        points.extend([
+            Vec2::new(0.0, -10.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(0.0, 10.0),
+            Vec2::new(-10.0, 0.0),
+            Vec2::ZERO,
+        ]);
+        assert_eq!(convex_hull(points), SQUARE);
+    }
+
+    #[test]
+    fn convex_hull_drops_duplicate_points() {
+        let points = [SQUARE, SQUARE].concat();
+        assert_eq!(convex_hull(points), SQUARE);
+    }
+
+    #[test]
+    fn circle_inside_outline_overlaps() {
+        assert!(square().overlaps_circle(Vec2::new(2.0, -3.0), 1.0));
+    }
+
+    #[test]
+    fn circle_crossing_one_edge_overlaps() {
+        assert!(square().overlaps_circle(Vec2::new(12.0, 0.0), 2.5));
+    }
+
+    #[test]
+    fn circle_outside_outline_misses() {
+        let square = square();
+        assert!(!square.overlaps_circle(Vec2::new(20.0, 0.0), 2.0));
+        // Beyond both edges at a corner but further than the radius from it.
+        assert!(!square.overlaps_circle(Vec2::new(12.0, 12.0), 2.5));
+    }
+}
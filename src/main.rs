@@ -1,32 +1,42 @@
-#![allow(clippy::type_complexity)]
-
-use bevy::{prelude::*, window::EnabledButtons};
+use bevy::{
+    audio::{AudioPlugin, SpatialScale},
+    prelude::*,
+};
 use bevy_embedded_assets::EmbeddedAssetPlugin;
 
 pub mod core;
 pub mod ui;
 
-use core::GamePlugin;
+use core::{
+    position::Playfield,
+    settings::{Settings, StartupWarnings},
+    GamePlugin,
+};
 use ui::UIPlugin;
 
 fn main() {
+    // Load settings first so window and audio start with them.
+    let mut warnings = StartupWarnings::default();
+    let settings = warnings.or_default(Settings::load());
+    let playfield = Playfield::from_args(&mut warnings);
+
     App::new()
         .add_plugins((
-            DefaultPlugins::build(DefaultPlugins).set(WindowPlugin {
-                // Disallow resizing window.
-                primary_window: Some(Window {
-                    resizable: false,
-                    enabled_buttons: EnabledButtons {
-                        minimize: true,
-                        maximize: false,
-                        close: true,
-                    },
+            DefaultPlugins::build(DefaultPlugins)
+                .set(WindowPlugin {
+                    primary_window: Some(settings.window(&playfield)),
                     ..default()
+                })
+                .set(AudioPlugin {
+                    global_volume: GlobalVolume::new(settings.master_volume),
+                    // Screen edges map to the listener's ears.
+                    spatial_scale: SpatialScale::new_2d(2.0 / playfield.view.x),
                 }),
-                ..default()
-            }),
             EmbeddedAssetPlugin::default(),
         ))
+        .insert_resource(settings)
+        .insert_resource(warnings)
+        .insert_resource(playfield)
         .add_plugins(UIPlugin)
         .add_plugins(GamePlugin)
         .run();
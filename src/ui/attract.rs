@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+
+use super::{hud::format_score, menu::button_text_style};
+use crate::core::score::HighScores;
+
+/// Seconds the high score and the demo are each shown for.
+const HIGH_SCORE_CYCLE_SECS: f32 = 5.0;
+
+#[derive(Component)]
+pub struct OnAttractScreen;
+
+/// High score table shown over the demo in turns.
+#[derive(Component)]
+pub struct HighScoreTable(Timer);
+
+pub fn setup_attract_screen(mut commands: Commands, high_scores: Res<HighScores>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::SpaceBetween,
+                    padding: UiRect::all(Val::Px(20.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            OnAttractScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "- Demo -",
+                TextStyle {
+                    color: Color::GRAY,
+                    ..button_text_style()
+                },
+            ));
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            padding: UiRect::all(Val::Px(30.0)),
+                            ..default()
+                        },
+                        background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                        visibility: Visibility::Hidden,
+                        ..default()
+                    },
+                    HighScoreTable(Timer::from_seconds(
+                        HIGH_SCORE_CYCLE_SECS,
+                        TimerMode::Repeating,
+                    )),
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "High Scores",
+                        TextStyle {
+                            color: Color::GOLD,
+                            ..button_text_style()
+                        },
+                    ));
+                    if high_scores.is_empty() {
+                        parent.spawn(TextBundle::from_section(
+                            "No scores yet",
+                            TextStyle {
+                                color: Color::GRAY,
+                                ..button_text_style()
+                            },
+                        ));
+                    }
+                    for (rank, score) in high_scores.iter().enumerate() {
+                        parent.spawn(TextBundle::from_section(
+                            format!("{}. {}", rank + 1, format_score(*score)),
+                            button_text_style(),
+                        ));
+                    }
+                });
+            parent.spawn(TextBundle::from_section(
+                "Press any key",
+                button_text_style(),
+            ));
+        });
+}
+
+/// Alternate between showing the high score and the demo.
+pub fn cycle_high_score_table(
+    time: Res<Time>,
+    mut table_query: Query<(&mut HighScoreTable, &mut Visibility)>,
+) {
+    for (mut table, mut visibility) in &mut table_query {
+        if table.0.tick(time.delta()).just_finished() {
+            *visibility = match *visibility {
+                Visibility::Hidden => Visibility::Inherited,
+                _ => Visibility::Hidden,
+            };
+        }
+    }
+}
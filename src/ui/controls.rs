@@ -0,0 +1,284 @@
+use bevy::prelude::*;
+use strum::IntoEnumIterator;
+
+use super::{
+    focus::{BackButton, Focusable},
+    menu::{button_bundle, button_text_style, MenuState},
+    InteractionChanged,
+};
+use crate::core::input::{Action, KeyBindings, MAX_BINDINGS};
+
+#[derive(Component)]
+pub struct OnControlsScreen;
+
+/// Button for one of the keys bound to an action.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub struct BindingSlot {
+    pub action: Action,
+    pub slot: usize,
+}
+
+/// Text showing the key bound to a [`BindingSlot`].
+#[derive(Component)]
+pub struct BindingSlotText(BindingSlot);
+
+/// Text for rebinding prompts and conflicts.
+#[derive(Component)]
+pub struct ControlsMessage;
+
+#[derive(Component, Clone, Copy)]
+pub enum ControlsButtonAction {
+    Reset,
+    Back,
+}
+
+/// Slot waiting for a key press.
+#[derive(Resource, Default)]
+pub struct Rebinding(pub Option<BindingSlot>);
+
+const DEFAULT_MESSAGE: &str = "Click a key to rebind it.";
+
+fn slot_text(bindings: &KeyBindings, rebinding: &Rebinding, slot: BindingSlot) -> String {
+    if rebinding.0 == Some(slot) {
+        return "...".to_owned();
+    }
+    bindings
+        .keys(slot.action)
+        .get(slot.slot)
+        .map(|key| format!("{key:?}"))
+        .unwrap_or_else(|| "-".to_owned())
+}
+
+pub fn setup_controls_menu(mut commands: Commands, bindings: Res<KeyBindings>) {
+    commands.insert_resource(Rebinding::default());
+
+    let slot_text_style = TextStyle {
+        font_size: 30.0,
+        ..button_text_style()
+    };
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                // Hide paused game behind menu.
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.8).into(),
+                ..default()
+            },
+            OnControlsScreen,
+        ))
+        .with_children(|parent| {
+            parent
+                // Menu container.
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    // Title
+                    parent.spawn(
+                        TextBundle::from_section(
+                            "Controls",
+                            TextStyle {
+                                font_size: 60.0,
+                                color: Color::WHITE,
+                                ..default()
+                            },
+                        )
+                        .with_style(Style {
+                            margin: UiRect::all(Val::Px(10.0)),
+                            ..default()
+                        }),
+                    );
+                    parent.spawn((
+                        TextBundle::from_section(
+                            DEFAULT_MESSAGE,
+                            TextStyle {
+                                font_size: 24.0,
+                                color: Color::GOLD,
+                                ..default()
+                            },
+                        )
+                        .with_style(Style {
+                            margin: UiRect::bottom(Val::Px(10.0)),
+                            ..default()
+                        }),
+                        ControlsMessage,
+                    ));
+
+                    // One row per action with a button per binding slot.
+                    for action in Action::iter() {
+                        parent
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    flex_direction: FlexDirection::Row,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                ..default()
+                            })
+                            .with_children(|parent| {
+                                parent.spawn(
+                                    TextBundle::from_section(
+                                        action.label(),
+                                        TextStyle {
+                                            font_size: 30.0,
+                                            color: Color::WHITE,
+                                            ..default()
+                                        },
+                                    )
+                                    .with_style(Style {
+                                        width: Val::Px(200.0),
+                                        ..default()
+                                    }),
+                                );
+                                for slot in 0..MAX_BINDINGS {
+                                    let slot = BindingSlot { action, slot };
+                                    let mut button = button_bundle();
+                                    button.style.width = Val::Px(180.0);
+                                    button.style.height = Val::Px(45.0);
+                                    button.style.margin = UiRect::all(Val::Px(6.0));
+                                    parent.spawn((button, slot, Focusable)).with_children(
+                                        |parent| {
+                                            parent.spawn((
+                                                TextBundle::from_section(
+                                                    slot_text(
+                                                        &bindings,
+                                                        &Rebinding::default(),
+                                                        slot,
+                                                    ),
+                                                    slot_text_style.clone(),
+                                                ),
+                                                BindingSlotText(slot),
+                                            ));
+                                        },
+                                    );
+                                }
+                            });
+                    }
+
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Row,
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            for (action, label) in [
+                                (ControlsButtonAction::Reset, "Reset"),
+                                (ControlsButtonAction::Back, "Back"),
+                            ] {
+                                let mut button = parent.spawn((button_bundle(), action, Focusable));
+                                if let ControlsButtonAction::Back = action {
+                                    button.insert(BackButton);
+                                }
+                                button.with_children(|parent| {
+                                    parent.spawn(TextBundle::from_section(
+                                        label,
+                                        button_text_style(),
+                                    ));
+                                });
+                            }
+                        });
+                });
+        });
+}
+
+pub fn update_controls_buttons(
+    slot_query: Query<(&Interaction, &BindingSlot), InteractionChanged<Button>>,
+    button_query: Query<(&Interaction, &ControlsButtonAction), InteractionChanged<Button>>,
+    mut bindings: ResMut<KeyBindings>,
+    mut rebinding: ResMut<Rebinding>,
+    mut message_query: Query<&mut Text, With<ControlsMessage>>,
+    mut menu_state: ResMut<NextState<MenuState>>,
+) {
+    let Ok(mut message) = message_query.get_single_mut() else {
+        return;
+    };
+    for (interaction, slot) in &slot_query {
+        if *interaction == Interaction::Pressed {
+            rebinding.0 = Some(*slot);
+            message.sections[0].value = format!(
+                "Press a key for {}. Esc to cancel, Backspace to clear.",
+                slot.action.label()
+            );
+        }
+    }
+    for (interaction, action) in &button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match action {
+            ControlsButtonAction::Reset => {
+                *bindings = KeyBindings::default();
+                rebinding.0 = None;
+                message.sections[0].value = DEFAULT_MESSAGE.to_owned();
+            }
+            ControlsButtonAction::Back => menu_state.set(MenuState::Settings),
+        }
+    }
+}
+
+/// Bind the next key pressed to the slot being rebound.
+pub fn capture_rebinding_key(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut bindings: ResMut<KeyBindings>,
+    mut rebinding: ResMut<Rebinding>,
+    mut message_query: Query<&mut Text, With<ControlsMessage>>,
+) {
+    // Ignore the key that started rebinding.
+    if rebinding.is_changed() {
+        return;
+    }
+    let (Some(slot), Some(key), Ok(mut message)) = (
+        rebinding.0,
+        keyboard_input.get_just_pressed().next().copied(),
+        message_query.get_single_mut(),
+    ) else {
+        return;
+    };
+
+    match key {
+        KeyCode::Escape => {}
+        KeyCode::Back | KeyCode::Delete => bindings.unbind(slot.action, slot.slot),
+        _ => match bindings.find(key) {
+            // Rebinding to the same key is a no-op.
+            Some((action, idx)) if action == slot.action && idx == slot.slot => {}
+            // Refuse keys used elsewhere so one key never triggers two actions.
+            Some((action, _)) => {
+                message.sections[0].value =
+                    format!("{key:?} is already bound to {}.", action.label());
+                return;
+            }
+            None => bindings.bind(slot.action, slot.slot, key),
+        },
+    }
+    rebinding.0 = None;
+    message.sections[0].value = DEFAULT_MESSAGE.to_owned();
+}
+
+/// Run condition to stop menu navigation from consuming the key being bound.
+pub fn not_rebinding(rebinding: Res<Rebinding>) -> bool {
+    rebinding.0.is_none()
+}
+
+pub fn update_binding_text(
+    bindings: Res<KeyBindings>,
+    rebinding: Res<Rebinding>,
+    mut text_query: Query<(&mut Text, &BindingSlotText)>,
+) {
+    for (mut text, slot_text_comp) in &mut text_query {
+        text.sections[0].value = slot_text(&bindings, &rebinding, slot_text_comp.0);
+    }
+}
@@ -0,0 +1,179 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::{
+    focus::{BackButton, Focusable},
+    menu::{button_bundle, button_text_style, MenuButtonAction},
+};
+use crate::core::animation::{AnimationId, LoopMode, SpriteAnimation, SpriteAnimations};
+
+const CREDITS: &str = include_str!("../../assets/Data/credits.ron");
+const CREDITS_VIEW_HEIGHT: f32 = 300.0;
+/// Pixels per second the attributions scroll up.
+const CREDITS_SCROLL_SPEED: f32 = 40.0;
+
+#[derive(Component)]
+pub struct OnCreditsScreen;
+
+/// Source of something used in the game. Loaded from `assets/Data/credits.ron`.
+#[derive(Deserialize)]
+struct CreditEntry {
+    name: String,
+    source: String,
+    license: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CreditSection {
+    heading: String,
+    entries: Vec<CreditEntry>,
+}
+
+/// Column of attributions scrolling up through the credits view. Starts again once it's scrolled past.
+#[derive(Component)]
+pub struct CreditsScroll {
+    top: f32,
+}
+
+pub fn setup_credits(mut commands: Commands, animations: Res<SpriteAnimations>) {
+    let sections: Vec<CreditSection> = ron::from_str(CREDITS).expect("Credits should be valid RON");
+    let heading_style = TextStyle {
+        font_size: 32.0,
+        color: Color::GOLD,
+        ..default()
+    };
+    let name_style = TextStyle {
+        font_size: 24.0,
+        ..button_text_style()
+    };
+    let source_style = TextStyle {
+        font_size: 18.0,
+        color: Color::GRAY,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            OnCreditsScreen,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    // Title
+                    parent.spawn((
+                        AtlasImageBundle {
+                            texture_atlas: animations.atlas(AnimationId::LogoCredits),
+                            style: Style {
+                                height: Val::Px(100.0),
+                                margin: UiRect::all(Val::Px(10.0)),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        SpriteAnimation::new(AnimationId::LogoCredits, 4.0, LoopMode::PingPong),
+                    ));
+                    // Attributions, clipped to the view.
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                width: Val::Px(700.0),
+                                height: Val::Px(CREDITS_VIEW_HEIGHT),
+                                overflow: Overflow::clip(),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            parent
+                                .spawn((
+                                    NodeBundle {
+                                        style: Style {
+                                            position_type: PositionType::Absolute,
+                                            top: Val::Px(CREDITS_VIEW_HEIGHT),
+                                            width: Val::Percent(100.0),
+                                            flex_direction: FlexDirection::Column,
+                                            align_items: AlignItems::Center,
+                                            row_gap: Val::Px(4.0),
+                                            ..default()
+                                        },
+                                        ..default()
+                                    },
+                                    CreditsScroll {
+                                        top: CREDITS_VIEW_HEIGHT,
+                                    },
+                                ))
+                                .with_children(|parent| {
+                                    for section in sections {
+                                        parent.spawn(
+                                            TextBundle::from_section(
+                                                section.heading,
+                                                heading_style.clone(),
+                                            )
+                                            .with_style(Style {
+                                                margin: UiRect::top(Val::Px(20.0)),
+                                                ..default()
+                                            }),
+                                        );
+                                        for entry in section.entries {
+                                            parent.spawn(TextBundle::from_section(
+                                                entry.name,
+                                                name_style.clone(),
+                                            ));
+                                            let source = match entry.license {
+                                                Some(license) => {
+                                                    format!("{} ({license})", entry.source)
+                                                }
+                                                None => entry.source,
+                                            };
+                                            parent.spawn(TextBundle::from_section(
+                                                source,
+                                                source_style.clone(),
+                                            ));
+                                        }
+                                    }
+                                });
+                        });
+                    parent
+                        .spawn((
+                            button_bundle(),
+                            MenuButtonAction::Back,
+                            Focusable,
+                            BackButton,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section("Back", button_text_style()));
+                        });
+                });
+        });
+}
+
+pub fn scroll_credits(
+    time: Res<Time>,
+    mut scroll_query: Query<(&mut CreditsScroll, &mut Style, &Node)>,
+) {
+    for (mut scroll, mut style, node) in &mut scroll_query {
+        scroll.top -= CREDITS_SCROLL_SPEED * time.delta_seconds();
+        if scroll.top < -node.size().y {
+            scroll.top = CREDITS_VIEW_HEIGHT;
+        }
+        style.top = Val::Px(scroll.top);
+    }
+}
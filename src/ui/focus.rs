@@ -0,0 +1,252 @@
+use bevy::prelude::*;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use super::InteractionChanged;
+use crate::core::{
+    audio::{PlaySound, SoundEvent},
+    input::ActiveGamepad,
+};
+
+pub const BUTTON_COLOR: Color = Color::NAVY;
+pub const HOVERED_BUTTON_COLOR: Color = Color::rgb(0.15, 0.15, 0.65);
+pub const PRESSED_BUTTON_COLOR: Color = Color::rgb(0.3, 0.3, 0.85);
+pub const FOCUS_BORDER_COLOR: Color = Color::WHITE;
+pub const FOCUS_BORDER_WIDTH: f32 = 3.0;
+
+/// Menu inputs from the keyboard or gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+pub enum MenuInput {
+    Up,
+    Down,
+    Left,
+    Right,
+    Activate,
+    Back,
+}
+
+impl MenuInput {
+    fn keys(&self) -> &'static [KeyCode] {
+        match self {
+            MenuInput::Up => &[KeyCode::Up],
+            MenuInput::Down => &[KeyCode::Down],
+            MenuInput::Left => &[KeyCode::Left],
+            MenuInput::Right => &[KeyCode::Right],
+            MenuInput::Activate => &[KeyCode::Return, KeyCode::NumpadEnter],
+            MenuInput::Back => &[KeyCode::Escape],
+        }
+    }
+
+    fn gamepad_button(&self) -> GamepadButtonType {
+        match self {
+            MenuInput::Up => GamepadButtonType::DPadUp,
+            MenuInput::Down => GamepadButtonType::DPadDown,
+            MenuInput::Left => GamepadButtonType::DPadLeft,
+            MenuInput::Right => GamepadButtonType::DPadRight,
+            MenuInput::Activate => GamepadButtonType::South,
+            MenuInput::Back => GamepadButtonType::East,
+        }
+    }
+
+    /// Direction to move focus in UI coordinates, where y points down.
+    fn direction(&self) -> Option<Vec2> {
+        match self {
+            MenuInput::Up => Some(Vec2::NEG_Y),
+            MenuInput::Down => Some(Vec2::Y),
+            MenuInput::Left => Some(Vec2::NEG_X),
+            MenuInput::Right => Some(Vec2::X),
+            MenuInput::Activate | MenuInput::Back => None,
+        }
+    }
+}
+
+/// Button that can be selected without a mouse.
+#[derive(Component)]
+pub struct Focusable;
+
+/// Button pressed when going back from a screen.
+#[derive(Component)]
+pub struct BackButton;
+
+/// Currently focused button and the button held by a keyboard or gamepad press.
+#[derive(Resource, Default)]
+pub struct Focused {
+    pub entity: Option<Entity>,
+    pressed: Option<Entity>,
+}
+
+/// Map pressed keys and gamepad buttons to menu inputs.
+pub fn update_menu_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    active_gamepad: Res<ActiveGamepad>,
+    mut menu_input: ResMut<Input<MenuInput>>,
+) {
+    menu_input.clear();
+    for input in MenuInput::iter() {
+        let gamepad_pressed = active_gamepad.0.is_some_and(|gamepad| {
+            gamepad_buttons.pressed(GamepadButton::new(gamepad, input.gamepad_button()))
+        });
+        if keyboard_input.any_pressed(input.keys().iter().copied()) || gamepad_pressed {
+            menu_input.press(input);
+        } else {
+            menu_input.release(input);
+        }
+    }
+}
+
+/// Find the closest focusable in a direction, favoring ones in line with the current focus.
+fn next_focus(
+    focusables: &[(Entity, Vec2)],
+    current: Vec2,
+    direction: Vec2,
+    wrap: bool,
+) -> Option<Entity> {
+    let score = |offset: Vec2| offset.dot(direction) + 2.0 * offset.perp_dot(direction).abs();
+    let nearest = focusables
+        .iter()
+        .filter(|(_, pos)| (*pos - current).dot(direction) > 0.0)
+        .min_by(|(_, pos_a), (_, pos_b)| {
+            score(*pos_a - current).total_cmp(&score(*pos_b - current))
+        });
+    if nearest.is_some() || !wrap {
+        return nearest.map(|(entity, _)| *entity);
+    }
+
+    // Wrap around to the furthest focusable in the opposite direction.
+    let wrapped_score =
+        |offset: Vec2| offset.dot(direction) - 2.0 * offset.perp_dot(direction).abs();
+    focusables
+        .iter()
+        .filter(|(_, pos)| (*pos - current).dot(direction) < 0.0)
+        .min_by(|(_, pos_a), (_, pos_b)| {
+            wrapped_score(*pos_a - current).total_cmp(&wrapped_score(*pos_b - current))
+        })
+        .map(|(entity, _)| *entity)
+}
+
+/// Move focus between buttons with the arrow keys or d-pad. Hovering with the mouse also focuses.
+pub fn navigate_focus(
+    menu_input: Res<Input<MenuInput>>,
+    hover_query: Query<(Entity, &Interaction), InteractionChanged<Focusable>>,
+    focusable_query: Query<(Entity, &GlobalTransform), With<Focusable>>,
+    mut focused: ResMut<Focused>,
+) {
+    if let Some((entity, _)) = hover_query
+        .iter()
+        .find(|(_, interaction)| **interaction == Interaction::Hovered)
+    {
+        focused.entity = Some(entity);
+    }
+
+    let Some(direction) = menu_input
+        .get_just_pressed()
+        .find_map(|input| input.direction())
+    else {
+        return;
+    };
+    let focusables: Vec<(Entity, Vec2)> = focusable_query
+        .iter()
+        .map(|(entity, transform)| (entity, transform.translation().truncate()))
+        .collect();
+
+    let current = focused.entity.and_then(|entity| {
+        focusables
+            .iter()
+            .find(|(focusable, _)| *focusable == entity)
+    });
+    focused.entity = match current {
+        // Only wrap vertically so left and right stop at the edge of a row.
+        Some((entity, pos)) => {
+            next_focus(&focusables, *pos, direction, direction.y != 0.0).or(Some(*entity))
+        }
+        // Start from the top-left button if nothing on screen is focused.
+        None => focusables
+            .iter()
+            .min_by(|(_, pos_a), (_, pos_b)| {
+                pos_a
+                    .y
+                    .total_cmp(&pos_b.y)
+                    .then(pos_a.x.total_cmp(&pos_b.x))
+            })
+            .map(|(entity, _)| *entity),
+    };
+}
+
+/// Press the focused or back button while activate or back is held, like a mouse click.
+pub fn activate_focused(
+    menu_input: Res<Input<MenuInput>>,
+    mut focused: ResMut<Focused>,
+    back_query: Query<Entity, With<BackButton>>,
+    mut interaction_query: Query<&mut Interaction>,
+) {
+    if menu_input.any_just_released([MenuInput::Activate, MenuInput::Back]) {
+        if let Some(mut interaction) = focused
+            .pressed
+            .take()
+            .and_then(|entity| interaction_query.get_mut(entity).ok())
+        {
+            interaction.set_if_neq(Interaction::None);
+        }
+    }
+
+    let pressed = if menu_input.just_pressed(MenuInput::Activate) {
+        focused.entity
+    } else if menu_input.just_pressed(MenuInput::Back) {
+        back_query.iter().next()
+    } else {
+        None
+    };
+    if let Some(entity) = pressed {
+        if let Ok(mut interaction) = interaction_query.get_mut(entity) {
+            *interaction = Interaction::Pressed;
+            focused.pressed = Some(entity);
+        }
+    }
+}
+
+/// Color buttons by interaction and outline the focused button.
+pub fn update_button_colors(
+    focused: Res<Focused>,
+    mut button_query: Query<(Entity, &Interaction, &mut BackgroundColor), With<Button>>,
+    mut border_query: Query<(Entity, &mut BorderColor), With<Focusable>>,
+) {
+    for (entity, interaction, mut color) in &mut button_query {
+        let new_color = match interaction {
+            Interaction::Pressed => PRESSED_BUTTON_COLOR,
+            Interaction::Hovered => HOVERED_BUTTON_COLOR,
+            Interaction::None if focused.entity == Some(entity) => HOVERED_BUTTON_COLOR,
+            Interaction::None => BUTTON_COLOR,
+        };
+        if color.0 != new_color {
+            color.0 = new_color;
+        }
+    }
+    for (entity, mut border_color) in &mut border_query {
+        let new_color = if focused.entity == Some(entity) {
+            FOCUS_BORDER_COLOR
+        } else {
+            Color::NONE
+        };
+        if border_color.0 != new_color {
+            border_color.0 = new_color;
+        }
+    }
+}
+
+/// Blip when focus moves to another button or a button is pressed.
+pub fn play_focus_blips(
+    focused: Res<Focused>,
+    mut prev_focused: Local<Option<Entity>>,
+    press_query: Query<&Interaction, InteractionChanged<Focusable>>,
+    mut sound_events: EventWriter<PlaySound>,
+) {
+    let focus_moved = focused.entity.is_some() && focused.entity != *prev_focused;
+    *prev_focused = focused.entity;
+    let pressed = press_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed);
+    if focus_moved || pressed {
+        sound_events.send(PlaySound::new(SoundEvent::UiBlip));
+    }
+}
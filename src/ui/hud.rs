@@ -0,0 +1,189 @@
+use bevy::prelude::*;
+
+use crate::core::{
+    asteroid::Wave,
+    lives::Lives,
+    score::{Combo, HighScores, Score},
+};
+
+const SHIP_ICON: &str = "embedded://Animations/obj_player/Default/000.png";
+const LIFE_ICON_SIZE: f32 = 28.0;
+/// Scores are zero padded to this many digits.
+const SCORE_DIGITS: usize = 6;
+
+/// Root of the in-game heads-up display.
+#[derive(Component)]
+pub struct Hud;
+
+/// Row of ship icons, one per remaining life.
+#[derive(Component)]
+pub struct LivesDisplay;
+
+#[derive(Component)]
+pub struct WaveText;
+
+#[derive(Component)]
+pub struct ScoreText;
+
+#[derive(Component)]
+pub struct HighScoreText;
+
+/// Combo multiplier, hidden while there's no combo.
+#[derive(Component)]
+pub struct ComboText;
+
+fn hud_text_style(font_size: f32, color: Color) -> TextStyle {
+    TextStyle {
+        font_size,
+        color,
+        ..default()
+    }
+}
+
+pub fn format_score(score: usize) -> String {
+    format!("{score:0SCORE_DIGITS$}")
+}
+
+/// One of the three equal width HUD columns.
+fn hud_column(align_items: AlignItems) -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Column,
+            flex_basis: Val::Px(0.0),
+            flex_grow: 1.0,
+            align_items,
+            ..default()
+        },
+        ..default()
+    }
+}
+
+pub fn setup_hud(mut commands: Commands, high_scores: Res<HighScores>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            Hud,
+        ))
+        .with_children(|parent| {
+            // Lives and wave on the left.
+            parent
+                .spawn(hud_column(AlignItems::FlexStart))
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                height: Val::Px(LIFE_ICON_SIZE),
+                                column_gap: Val::Px(4.0),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        LivesDisplay,
+                    ));
+                    parent.spawn((
+                        TextBundle::from_sections([
+                            TextSection::new("WAVE ", hud_text_style(24.0, Color::GRAY)),
+                            TextSection::from_style(hud_text_style(24.0, Color::WHITE)),
+                        ]),
+                        WaveText,
+                    ));
+                });
+
+            // Score, high score and multiplier in the middle.
+            parent
+                .spawn(hud_column(AlignItems::Center))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            format_score(0),
+                            hud_text_style(40.0, Color::GOLD),
+                        ),
+                        ScoreText,
+                    ));
+                    parent.spawn((
+                        TextBundle::from_sections([
+                            TextSection::new("HI ", hud_text_style(24.0, Color::GRAY)),
+                            TextSection::new(
+                                format_score(high_scores.best()),
+                                hud_text_style(24.0, Color::WHITE),
+                            ),
+                        ]),
+                        HighScoreText,
+                    ));
+                    parent.spawn((
+                        TextBundle::from_section("", hud_text_style(30.0, Color::GOLD)),
+                        ComboText,
+                    ));
+                });
+
+            // Keep the right clear for the minimap.
+            parent.spawn(hud_column(AlignItems::FlexEnd));
+        });
+}
+
+pub fn update_lives_display(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    lives: Res<Lives>,
+    lives_query: Query<Entity, With<LivesDisplay>>,
+) {
+    let Ok(display) = lives_query.get_single() else {
+        return;
+    };
+    let icon = asset_server.load(SHIP_ICON);
+    commands
+        .entity(display)
+        .despawn_descendants()
+        .with_children(|parent| {
+            for _ in 0..lives.0 {
+                parent.spawn(ImageBundle {
+                    image: icon.clone().into(),
+                    style: Style {
+                        width: Val::Px(LIFE_ICON_SIZE),
+                        height: Val::Px(LIFE_ICON_SIZE),
+                        ..default()
+                    },
+                    ..default()
+                });
+            }
+        });
+}
+
+pub fn update_wave_text(wave: Res<Wave>, mut text_query: Query<&mut Text, With<WaveText>>) {
+    for mut text in &mut text_query {
+        text.sections[1].value = wave.0.to_string();
+    }
+}
+
+/// Show the score, and the high score once it's beaten.
+pub fn update_score_text(
+    score: Res<Score>,
+    high_scores: Res<HighScores>,
+    mut score_query: Query<&mut Text, (With<ScoreText>, Without<HighScoreText>)>,
+    mut high_score_query: Query<&mut Text, With<HighScoreText>>,
+) {
+    for mut text in &mut score_query {
+        text.sections[0].value = format_score(score.0);
+    }
+    for mut text in &mut high_score_query {
+        text.sections[1].value = format_score(score.0.max(high_scores.best()));
+    }
+}
+
+pub fn update_combo_text(combo: Res<Combo>, mut text_query: Query<&mut Text, With<ComboText>>) {
+    for mut text in &mut text_query {
+        text.sections[0].value = if combo.multiplier > 1 {
+            format!("x{}", combo.multiplier)
+        } else {
+            String::new()
+        };
+    }
+}
@@ -1,6 +1,13 @@
 use bevy::{app::AppExit, prelude::*};
 
-use crate::core::AppState;
+use super::{
+    focus::{Focusable, BUTTON_COLOR, FOCUS_BORDER_WIDTH},
+    InteractionChanged,
+};
+use crate::core::{
+    animation::{AnimationId, LoopMode, SpriteAnimation, SpriteAnimations},
+    AppState,
+};
 
 // Tag component used to tag entities added on a screen
 #[derive(Component)]
@@ -9,6 +16,11 @@ pub struct OnMainMenuScreen;
 #[derive(Component)]
 pub enum MenuButtonAction {
     Play,
+    Resume,
+    Settings,
+    Credits,
+    /// Back to the main menu.
+    Back,
     Quit,
 }
 
@@ -16,29 +28,42 @@ pub enum MenuButtonAction {
 pub enum MenuState {
     #[default]
     Main,
+    Settings,
+    Controls,
+    Credits,
+    Pause,
     Disabled,
 }
 
-// https://bevyengine.org/examples/UI%20(User%20Interface)/button/
-pub fn setup_menu(mut commands: Commands) {
-    let button_style = Style {
-        width: Val::Px(250.0),
-        height: Val::Px(65.0),
-        margin: UiRect::all(Val::Px(20.0)),
-        justify_content: JustifyContent::Center,
-        align_items: AlignItems::Center,
+pub fn button_bundle() -> ButtonBundle {
+    ButtonBundle {
+        style: Style {
+            width: Val::Px(250.0),
+            height: Val::Px(65.0),
+            margin: UiRect::all(Val::Px(20.0)),
+            border: UiRect::all(Val::Px(FOCUS_BORDER_WIDTH)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        background_color: BUTTON_COLOR.into(),
+        border_color: Color::NONE.into(),
         ..default()
-    };
-    let button_text_style = TextStyle {
+    }
+}
+
+pub fn button_text_style() -> TextStyle {
+    TextStyle {
         font_size: 40.0,
         color: Color::WHITE,
         ..default()
-    };
-    let button_bundle_style = ButtonBundle {
-        style: button_style.clone(),
-        background_color: Color::NAVY.into(),
-        ..default()
-    };
+    }
+}
+
+// https://bevyengine.org/examples/UI%20(User%20Interface)/button/
+pub fn setup_menu(mut commands: Commands, animations: Res<SpriteAnimations>) {
+    let button_text_style = button_text_style();
+    let button_bundle_style = button_bundle();
 
     commands
         .spawn((
@@ -69,29 +94,59 @@ pub fn setup_menu(mut commands: Commands) {
                     // TODO: Use scaled asteroid sprite as bg?
 
                     // Title
-                    parent.spawn(
-                        TextBundle::from_section(
-                            "Asteroids",
-                            TextStyle {
-                                font_size: 80.0,
-                                color: Color::WHITE,
+                    parent.spawn((
+                        AtlasImageBundle {
+                            texture_atlas: animations.atlas(AnimationId::LogoAsteroids),
+                            style: Style {
+                                height: Val::Px(150.0),
+                                margin: UiRect::all(Val::Px(20.0)),
                                 ..default()
                             },
-                        )
-                        .with_style(Style {
-                            margin: UiRect::all(Val::Px(50.0)),
                             ..default()
-                        }),
-                    );
+                        },
+                        SpriteAnimation::new(AnimationId::LogoAsteroids, 1.0, LoopMode::Loop),
+                    ));
                     // Menu buttons.
                     parent
-                        .spawn((button_bundle_style.clone(), MenuButtonAction::Play))
+                        .spawn((
+                            button_bundle_style.clone(),
+                            MenuButtonAction::Play,
+                            Focusable,
+                        ))
                         .with_children(|parent| {
                             parent
                                 .spawn(TextBundle::from_section("Play", button_text_style.clone()));
                         });
                     parent
-                        .spawn((button_bundle_style.clone(), MenuButtonAction::Quit))
+                        .spawn((
+                            button_bundle_style.clone(),
+                            MenuButtonAction::Settings,
+                            Focusable,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                "Settings",
+                                button_text_style.clone(),
+                            ));
+                        });
+                    parent
+                        .spawn((
+                            button_bundle_style.clone(),
+                            MenuButtonAction::Credits,
+                            Focusable,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                "Credits",
+                                button_text_style.clone(),
+                            ));
+                        });
+                    parent
+                        .spawn((
+                            button_bundle_style.clone(),
+                            MenuButtonAction::Quit,
+                            Focusable,
+                        ))
                         .with_children(|parent| {
                             parent
                                 .spawn(TextBundle::from_section("Quit", button_text_style.clone()));
@@ -101,10 +156,7 @@ pub fn setup_menu(mut commands: Commands) {
 }
 
 pub fn update_menu_game_state(
-    interaction_query: Query<
-        (&Interaction, &MenuButtonAction),
-        (Changed<Interaction>, With<Button>),
-    >,
+    interaction_query: Query<(&Interaction, &MenuButtonAction), InteractionChanged<Button>>,
     mut app_exit_events: EventWriter<AppExit>,
     mut menu_state: ResMut<NextState<MenuState>>,
     mut game_state: ResMut<NextState<AppState>>,
@@ -112,10 +164,13 @@ pub fn update_menu_game_state(
     for (interaction, menu_button_action) in &interaction_query {
         if *interaction == Interaction::Pressed {
             match menu_button_action {
-                MenuButtonAction::Play => {
+                MenuButtonAction::Play | MenuButtonAction::Resume => {
                     game_state.set(AppState::InGame);
                     menu_state.set(MenuState::Disabled);
                 }
+                MenuButtonAction::Settings => menu_state.set(MenuState::Settings),
+                MenuButtonAction::Credits => menu_state.set(MenuState::Credits),
+                MenuButtonAction::Back => menu_state.set(MenuState::Main),
                 MenuButtonAction::Quit => app_exit_events.send(AppExit),
             }
         }
@@ -0,0 +1,116 @@
+use bevy::{prelude::*, utils::HashSet};
+
+use crate::core::{
+    asteroid::{Asteroid, AsteroidSize},
+    player::Player,
+    position::{Playfield, Position},
+    vector::Outline,
+};
+
+const MINIMAP_WIDTH: f32 = 180.0;
+const SHIP_DOT_SIZE: f32 = 6.0;
+
+/// Map of the whole world in the corner of the screen. Only shown when the camera follows the ship.
+#[derive(Component)]
+pub struct Minimap;
+
+/// Marks where an entity is on the minimap.
+#[derive(Component)]
+pub struct MinimapDot(Entity);
+
+fn asteroid_dot_size(size: AsteroidSize) -> f32 {
+    match size {
+        AsteroidSize::Large => 5.0,
+        AsteroidSize::Medium => 4.0,
+        AsteroidSize::Small => 3.0,
+        AsteroidSize::Tiny => 2.0,
+    }
+}
+
+pub fn setup_minimap(mut commands: Commands, playfield: Res<Playfield>) {
+    if !playfield.follows_ship() {
+        return;
+    }
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(5.0),
+                right: Val::Px(5.0),
+                width: Val::Px(MINIMAP_WIDTH),
+                height: Val::Px(MINIMAP_WIDTH * playfield.size.y / playfield.size.x),
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
+            border_color: Color::GRAY.into(),
+            ..default()
+        },
+        Minimap,
+    ));
+}
+
+/// Entities shown on the minimap.
+type Tracked = Or<(With<Asteroid>, With<Player>)>;
+
+/// Add, move and remove dots for the ship and asteroids.
+pub fn update_minimap(
+    mut commands: Commands,
+    playfield: Res<Playfield>,
+    minimap_query: Query<Entity, With<Minimap>>,
+    tracked_query: Query<(&Position, &Outline, Option<&Asteroid>), Tracked>,
+    new_tracked_query: Query<Entity, (Added<Position>, Tracked)>,
+    mut dot_query: Query<(Entity, &MinimapDot, &mut Style)>,
+) {
+    let Ok(minimap) = minimap_query.get_single() else {
+        return;
+    };
+    // Position of an entity as a percent of the map, from the top left.
+    let map_position = |pos: Vec2| {
+        let normalized = (pos - playfield.min()) / playfield.size;
+        Vec2::new(normalized.x, 1.0 - normalized.y) * 100.0
+    };
+
+    let mut dotted = HashSet::new();
+    for (dot_entity, dot, mut style) in &mut dot_query {
+        let Ok((pos, _, _)) = tracked_query.get(dot.0) else {
+            commands.entity(dot_entity).despawn_recursive();
+            continue;
+        };
+        let map_pos = map_position(pos.0);
+        style.left = Val::Percent(map_pos.x);
+        style.top = Val::Percent(map_pos.y);
+        dotted.insert(dot.0);
+    }
+
+    for entity in &new_tracked_query {
+        let Ok((pos, outline, asteroid)) = tracked_query.get(entity) else {
+            continue;
+        };
+        if dotted.contains(&entity) {
+            continue;
+        }
+        let size = asteroid.map_or(SHIP_DOT_SIZE, |asteroid| asteroid_dot_size(asteroid.size));
+        let map_pos = map_position(pos.0);
+        let dot = commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        left: Val::Percent(map_pos.x),
+                        top: Val::Percent(map_pos.y),
+                        width: Val::Px(size),
+                        height: Val::Px(size),
+                        // Center the dot on the position.
+                        margin: UiRect::all(Val::Px(-size / 2.0)),
+                        ..default()
+                    },
+                    background_color: outline.color.into(),
+                    ..default()
+                },
+                MinimapDot(entity),
+            ))
+            .id();
+        commands.entity(minimap).add_child(dot);
+    }
+}
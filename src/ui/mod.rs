@@ -1,16 +1,50 @@
-use bevy::prelude::*;
+use bevy::{input::InputSystem, prelude::*, ui::UiSystem};
 
-use crate::core::AppState;
+use crate::core::{
+    asteroid::Wave,
+    input::{save_bindings, KeyBindings},
+    lives::Lives,
+    score::{Combo, Score},
+    settings::{save_settings, Settings},
+    AppState,
+};
 
 use self::{
+    attract::{cycle_high_score_table, setup_attract_screen, OnAttractScreen},
+    controls::{
+        capture_rebinding_key, not_rebinding, setup_controls_menu, update_binding_text,
+        update_controls_buttons, OnControlsScreen, Rebinding,
+    },
+    credits::{scroll_credits, setup_credits, OnCreditsScreen},
+    focus::{
+        activate_focused, navigate_focus, play_focus_blips, update_button_colors,
+        update_menu_input, Focused, MenuInput,
+    },
+    hud::{
+        setup_hud, update_combo_text, update_lives_display, update_score_text, update_wave_text,
+        Hud,
+    },
     menu::{setup_menu, update_menu_game_state, MenuState, OnMainMenuScreen},
+    minimap::{setup_minimap, update_minimap, Minimap},
     pause::{setup_pause_message, OnPauseScreen},
-    score::{add_score_ui, update_score_text, ScoreText},
+    settings::{
+        setup_settings_menu, step_focused_slider, update_settings_buttons, update_settings_text,
+        update_sliders, OnSettingsScreen,
+    },
 };
 
+pub mod attract;
+pub mod controls;
+pub mod credits;
+pub mod focus;
+pub mod hud;
 pub mod menu;
+pub mod minimap;
 pub mod pause;
-pub mod score;
+pub mod settings;
+
+/// Query filter for `T`s whose interaction changed this frame.
+pub type InteractionChanged<T> = (Changed<Interaction>, With<T>);
 
 pub struct UIPlugin;
 
@@ -18,34 +52,107 @@ pub struct UIPlugin;
 impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
         app.add_state::<MenuState>()
-            .add_systems(OnEnter(AppState::Menu), setup_menu)
+            .init_resource::<Rebinding>()
+            .init_resource::<Focused>()
+            .init_resource::<Input<MenuInput>>()
+            // Keyboard and gamepad menu navigation.
+            .add_systems(
+                PreUpdate,
+                (
+                    update_menu_input,
+                    (navigate_focus, activate_focused).run_if(not_rebinding),
+                )
+                    .chain()
+                    .after(InputSystem)
+                    .after(UiSystem::Focus),
+            )
+            .add_systems(Update, (update_button_colors, play_focus_blips))
+            .add_systems(OnEnter(MenuState::Main), setup_menu)
             .add_systems(
                 OnTransition {
                     from: AppState::Menu,
                     to: AppState::InGame,
                 },
-                add_score_ui,
+                (setup_hud, setup_minimap),
             )
+            // HUD only updates when what it shows changes.
             .add_systems(
-                FixedUpdate,
-                update_score_text.run_if(in_state(AppState::InGame)),
+                Update,
+                (
+                    update_lives_display.run_if(resource_changed::<Lives>()),
+                    update_wave_text.run_if(resource_changed::<Wave>()),
+                    update_score_text.run_if(resource_changed::<Score>()),
+                    update_combo_text.run_if(resource_changed::<Combo>()),
+                )
+                    .run_if(in_state(AppState::InGame)),
             )
+            .add_systems(Update, update_minimap.run_if(in_state(AppState::InGame)))
             .add_systems(
                 FixedUpdate,
-                (update_menu_game_state).run_if(in_state(AppState::Menu)),
+                (update_menu_game_state)
+                    .run_if(in_state(AppState::Menu).or_else(in_state(AppState::Paused))),
             )
             // Setup and delete pause message.
-            .add_systems(OnEnter(AppState::Paused), setup_pause_message)
-            .add_systems(OnExit(AppState::Paused), despawn_screen::<OnPauseScreen>)
+            .add_systems(OnEnter(MenuState::Pause), setup_pause_message)
+            .add_systems(OnExit(MenuState::Pause), despawn_screen::<OnPauseScreen>)
+            // Setup, update, and save settings.
+            .add_systems(OnEnter(MenuState::Settings), setup_settings_menu)
+            .add_systems(
+                Update,
+                (
+                    update_sliders,
+                    step_focused_slider,
+                    update_settings_buttons,
+                    update_settings_text.run_if(resource_changed::<Settings>()),
+                )
+                    .chain()
+                    .run_if(in_state(MenuState::Settings)),
+            )
+            .add_systems(
+                OnExit(MenuState::Settings),
+                (despawn_screen::<OnSettingsScreen>, save_settings),
+            )
+            // Setup, update, and save key bindings.
+            .add_systems(OnEnter(MenuState::Controls), setup_controls_menu)
+            .add_systems(
+                Update,
+                (
+                    update_controls_buttons,
+                    capture_rebinding_key,
+                    update_binding_text.run_if(
+                        resource_changed::<KeyBindings>().or_else(resource_changed::<Rebinding>()),
+                    ),
+                )
+                    .chain()
+                    .run_if(in_state(MenuState::Controls)),
+            )
+            .add_systems(
+                OnExit(MenuState::Controls),
+                (despawn_screen::<OnControlsScreen>, save_bindings),
+            )
+            // Setup, scroll, and delete credits.
+            .add_systems(OnEnter(MenuState::Credits), setup_credits)
+            .add_systems(Update, scroll_credits.run_if(in_state(MenuState::Credits)))
+            .add_systems(
+                OnExit(MenuState::Credits),
+                despawn_screen::<OnCreditsScreen>,
+            )
+            // Setup, cycle, and delete attract mode overlay.
+            .add_systems(OnEnter(AppState::Attract), setup_attract_screen)
+            .add_systems(
+                Update,
+                cycle_high_score_table.run_if(in_state(AppState::Attract)),
+            )
+            .add_systems(OnExit(AppState::Attract), despawn_screen::<OnAttractScreen>)
             // Delete menu nodes on exiting menu.
-            .add_systems(OnExit(AppState::Menu), despawn_screen::<OnMainMenuScreen>)
-            // Delete score text on exiting game.
+            .add_systems(OnExit(MenuState::Main), despawn_screen::<OnMainMenuScreen>)
+            // Delete HUD and minimap on exiting game.
             .add_systems(
                 OnTransition {
                     from: AppState::InGame,
                     to: AppState::Menu,
                 },
-                despawn_screen::<ScoreText>,
+                (despawn_screen::<Hud>, despawn_screen::<Minimap>),
             );
     }
 }
@@ -1,5 +1,10 @@
 use bevy::prelude::*;
 
+use super::{
+    focus::{BackButton, Focusable},
+    menu::{button_bundle, button_text_style, MenuButtonAction},
+};
+
 #[derive(Component)]
 pub struct OnPauseScreen;
 
@@ -47,6 +52,22 @@ pub fn setup_pause_message(mut commands: Commands) {
                             ..default()
                         }),
                     );
+                    // Pause buttons.
+                    parent
+                        .spawn((
+                            button_bundle(),
+                            MenuButtonAction::Resume,
+                            Focusable,
+                            BackButton,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section("Resume", button_text_style()));
+                        });
+                    parent
+                        .spawn((button_bundle(), MenuButtonAction::Settings, Focusable))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section("Settings", button_text_style()));
+                        });
                 });
         });
 }
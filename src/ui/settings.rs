@@ -0,0 +1,468 @@
+use bevy::{prelude::*, ui::RelativeCursorPosition};
+
+use super::{
+    focus::{BackButton, Focusable, Focused, MenuInput, FOCUS_BORDER_WIDTH},
+    menu::{button_bundle, button_text_style, MenuState},
+    InteractionChanged,
+};
+use crate::core::{
+    audio::AudioChannel,
+    settings::{cycle, Settings},
+    AppState,
+};
+
+#[derive(Component)]
+pub struct OnSettingsScreen;
+
+#[derive(Component, Clone, Copy)]
+pub enum SettingSlider {
+    Master,
+    Music,
+    Sfx,
+    Ui,
+    Bloom,
+    Scanlines,
+    Distortion,
+    Aberration,
+}
+
+impl SettingSlider {
+    fn channel(&self) -> Option<AudioChannel> {
+        match self {
+            SettingSlider::Music => Some(AudioChannel::Music),
+            SettingSlider::Sfx => Some(AudioChannel::Sfx),
+            SettingSlider::Ui => Some(AudioChannel::Ui),
+            _ => None,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            SettingSlider::Master => "Master",
+            SettingSlider::Music => "Music",
+            SettingSlider::Sfx => "SFX",
+            SettingSlider::Ui => "UI",
+            SettingSlider::Bloom => "Bloom",
+            SettingSlider::Scanlines => "Scanlines",
+            SettingSlider::Distortion => "Distortion",
+            SettingSlider::Aberration => "Aberration",
+        }
+    }
+
+    fn value(&self, settings: &Settings) -> f32 {
+        match self {
+            SettingSlider::Master => settings.master_volume,
+            SettingSlider::Music => settings.music_volume,
+            SettingSlider::Sfx => settings.sfx_volume,
+            SettingSlider::Ui => settings.ui_volume,
+            SettingSlider::Bloom => settings.bloom,
+            SettingSlider::Scanlines => settings.scanlines,
+            SettingSlider::Distortion => settings.barrel_distortion,
+            SettingSlider::Aberration => settings.chromatic_aberration,
+        }
+    }
+
+    fn value_mut<'a>(&self, settings: &'a mut Settings) -> &'a mut f32 {
+        match self {
+            SettingSlider::Master => &mut settings.master_volume,
+            SettingSlider::Music => &mut settings.music_volume,
+            SettingSlider::Sfx => &mut settings.sfx_volume,
+            SettingSlider::Ui => &mut settings.ui_volume,
+            SettingSlider::Bloom => &mut settings.bloom,
+            SettingSlider::Scanlines => &mut settings.scanlines,
+            SettingSlider::Distortion => &mut settings.barrel_distortion,
+            SettingSlider::Aberration => &mut settings.chromatic_aberration,
+        }
+    }
+}
+
+/// Filled portion of a [`SettingSlider`].
+#[derive(Component)]
+pub struct SliderFill(SettingSlider);
+
+#[derive(Component, Clone, Copy)]
+pub enum SettingsButtonAction {
+    Mute(AudioChannel),
+    Fullscreen,
+    Vsync,
+    SoundAttenuation,
+    ScreenShake,
+    Theme,
+    WindowScale,
+    Difficulty,
+    Controls,
+    Back,
+}
+
+impl SettingsButtonAction {
+    fn label(&self) -> &'static str {
+        match self {
+            SettingsButtonAction::Mute(_) => "Mute",
+            SettingsButtonAction::Fullscreen => "Fullscreen",
+            SettingsButtonAction::Vsync => "VSync",
+            SettingsButtonAction::SoundAttenuation => "Sound Falloff",
+            SettingsButtonAction::ScreenShake => "Screen Shake",
+            SettingsButtonAction::Theme => "Theme",
+            SettingsButtonAction::WindowScale => "Scale",
+            SettingsButtonAction::Difficulty => "Difficulty",
+            SettingsButtonAction::Controls => "Controls",
+            SettingsButtonAction::Back => "Back",
+        }
+    }
+
+    fn value(&self, settings: &Settings) -> String {
+        let on_off = |value: bool| if value { "On" } else { "Off" }.to_owned();
+        match self {
+            // Show whether the channel is audible.
+            SettingsButtonAction::Mute(channel) => on_off(!settings.channel_muted(*channel)),
+            SettingsButtonAction::Fullscreen => on_off(settings.fullscreen),
+            SettingsButtonAction::Vsync => on_off(settings.vsync),
+            SettingsButtonAction::SoundAttenuation => on_off(settings.sound_attenuation),
+            SettingsButtonAction::ScreenShake => on_off(settings.screen_shake),
+            SettingsButtonAction::Theme => format!("{:?}", settings.theme),
+            SettingsButtonAction::WindowScale => {
+                format!("{}%", (settings.window_scale.factor() * 100.0).round())
+            }
+            SettingsButtonAction::Difficulty => format!("{:?}", settings.difficulty),
+            SettingsButtonAction::Controls | SettingsButtonAction::Back => self.label().to_owned(),
+        }
+    }
+}
+
+/// Button text showing the current value of a setting.
+#[derive(Component)]
+pub struct SettingsValueText(SettingsButtonAction);
+
+const ROW_LABEL_WIDTH: f32 = 200.0;
+const SLIDER_WIDTH: f32 = 250.0;
+const SLIDER_HEIGHT: f32 = 24.0;
+const MUTE_BUTTON_WIDTH: f32 = 90.0;
+const TOGGLE_WIDTH: f32 = 150.0;
+/// Value change per left or right press on a focused slider.
+const SLIDER_STEP: f32 = 0.1;
+
+fn row_bundle() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        ..default()
+    }
+}
+
+fn row_label(label: &str) -> TextBundle {
+    TextBundle::from_section(
+        label,
+        TextStyle {
+            font_size: 30.0,
+            color: Color::WHITE,
+            ..default()
+        },
+    )
+    .with_style(Style {
+        width: Val::Px(ROW_LABEL_WIDTH),
+        ..default()
+    })
+}
+
+fn column_bundle() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Column,
+            margin: UiRect::horizontal(Val::Px(10.0)),
+            ..default()
+        },
+        ..default()
+    }
+}
+
+/// Spawn a labelled slider, with a mute button if it sets a channel's volume.
+fn spawn_slider_row(parent: &mut ChildBuilder, slider: SettingSlider, settings: &Settings) {
+    parent.spawn(row_bundle()).with_children(|parent| {
+        parent.spawn(row_label(slider.label()));
+        parent
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Px(SLIDER_WIDTH),
+                        height: Val::Px(SLIDER_HEIGHT),
+                        margin: UiRect::all(Val::Px(8.0)),
+                        border: UiRect::all(Val::Px(FOCUS_BORDER_WIDTH)),
+                        ..default()
+                    },
+                    background_color: Color::DARK_GRAY.into(),
+                    ..default()
+                },
+                Interaction::default(),
+                RelativeCursorPosition::default(),
+                Focusable,
+                slider,
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Percent(slider.value(settings) * 100.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        background_color: Color::NAVY.into(),
+                        ..default()
+                    },
+                    SliderFill(slider),
+                ));
+            });
+        if let Some(channel) = slider.channel() {
+            spawn_value_button(
+                parent,
+                SettingsButtonAction::Mute(channel),
+                MUTE_BUTTON_WIDTH,
+                settings,
+            );
+        } else if let SettingSlider::Master = slider {
+            // Keep the master slider aligned with rows that have a mute button.
+            parent.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(MUTE_BUTTON_WIDTH + 8.0),
+                    ..default()
+                },
+                ..default()
+            });
+        }
+    });
+}
+
+/// Spawn a button showing the value of its setting.
+fn spawn_value_button(
+    parent: &mut ChildBuilder,
+    action: SettingsButtonAction,
+    width: f32,
+    settings: &Settings,
+) {
+    let mut button = button_bundle();
+    button.style.width = Val::Px(width);
+    button.style.height = Val::Px(42.0);
+    button.style.margin = UiRect::all(Val::Px(4.0));
+    parent
+        .spawn((button, action, Focusable))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    action.value(settings),
+                    TextStyle {
+                        font_size: 30.0,
+                        ..button_text_style()
+                    },
+                ),
+                SettingsValueText(action),
+            ));
+        });
+}
+
+pub fn setup_settings_menu(mut commands: Commands, settings: Res<Settings>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                // Hide paused game behind menu.
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.8).into(),
+                ..default()
+            },
+            OnSettingsScreen,
+        ))
+        .with_children(|parent| {
+            parent
+                // Menu container.
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    // Title
+                    parent.spawn(
+                        TextBundle::from_section(
+                            "Settings",
+                            TextStyle {
+                                font_size: 60.0,
+                                color: Color::WHITE,
+                                ..default()
+                            },
+                        )
+                        .with_style(Style {
+                            margin: UiRect::all(Val::Px(10.0)),
+                            ..default()
+                        }),
+                    );
+
+                    // Volume sliders beside post-processing sliders.
+                    parent.spawn(row_bundle()).with_children(|parent| {
+                        for column in [
+                            [
+                                SettingSlider::Master,
+                                SettingSlider::Music,
+                                SettingSlider::Sfx,
+                                SettingSlider::Ui,
+                            ],
+                            [
+                                SettingSlider::Bloom,
+                                SettingSlider::Scanlines,
+                                SettingSlider::Distortion,
+                                SettingSlider::Aberration,
+                            ],
+                        ] {
+                            parent.spawn(column_bundle()).with_children(|parent| {
+                                for slider in column {
+                                    spawn_slider_row(parent, slider, &settings);
+                                }
+                            });
+                        }
+                    });
+                    // Toggles and selectors, two per row.
+                    for actions in [
+                        SettingsButtonAction::Fullscreen,
+                        SettingsButtonAction::Vsync,
+                        SettingsButtonAction::WindowScale,
+                        SettingsButtonAction::SoundAttenuation,
+                        SettingsButtonAction::ScreenShake,
+                        SettingsButtonAction::Theme,
+                        SettingsButtonAction::Difficulty,
+                    ]
+                    .chunks(2)
+                    {
+                        parent.spawn(row_bundle()).with_children(|parent| {
+                            for action in actions {
+                                parent.spawn(row_label(action.label()));
+                                spawn_value_button(parent, *action, TOGGLE_WIDTH, &settings);
+                            }
+                        });
+                    }
+
+                    parent.spawn(row_bundle()).with_children(|parent| {
+                        for action in [SettingsButtonAction::Controls, SettingsButtonAction::Back] {
+                            let mut button = parent.spawn((button_bundle(), action, Focusable));
+                            if let SettingsButtonAction::Back = action {
+                                button.insert(BackButton);
+                            }
+                            button.with_children(|parent| {
+                                parent.spawn(TextBundle::from_section(
+                                    action.label(),
+                                    button_text_style(),
+                                ));
+                            });
+                        }
+                    });
+                });
+        });
+}
+
+/// Set slider values from cursor position while a slider is held.
+pub fn update_sliders(
+    mouse_input: Res<Input<MouseButton>>,
+    mut settings: ResMut<Settings>,
+    slider_query: Query<(&Interaction, &RelativeCursorPosition, &SettingSlider)>,
+) {
+    // Sliders can also be pressed from the keyboard so check the mouse is held.
+    if !mouse_input.pressed(MouseButton::Left) {
+        return;
+    }
+    for (interaction, cursor_pos, slider) in &slider_query {
+        let (Interaction::Pressed, Some(pos)) = (interaction, cursor_pos.normalized) else {
+            continue;
+        };
+        let value = pos.x.clamp(0.0, 1.0);
+        // Only trigger change detection if the value actually changed.
+        if *slider.value_mut(settings.bypass_change_detection()) != value {
+            *slider.value_mut(&mut settings) = value;
+        }
+    }
+}
+
+/// Step the focused slider's value with left and right.
+pub fn step_focused_slider(
+    menu_input: Res<Input<MenuInput>>,
+    focused: Res<Focused>,
+    slider_query: Query<&SettingSlider>,
+    mut settings: ResMut<Settings>,
+) {
+    let Some(slider) = focused
+        .entity
+        .and_then(|entity| slider_query.get(entity).ok())
+    else {
+        return;
+    };
+    let step = if menu_input.just_pressed(MenuInput::Left) {
+        -SLIDER_STEP
+    } else if menu_input.just_pressed(MenuInput::Right) {
+        SLIDER_STEP
+    } else {
+        return;
+    };
+    let value = slider.value_mut(&mut settings);
+    *value = (*value + step).clamp(0.0, 1.0);
+}
+
+pub fn update_settings_buttons(
+    interaction_query: Query<(&Interaction, &SettingsButtonAction), InteractionChanged<Button>>,
+    app_state: Res<State<AppState>>,
+    mut settings: ResMut<Settings>,
+    mut menu_state: ResMut<NextState<MenuState>>,
+) {
+    for (interaction, action) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match action {
+            SettingsButtonAction::Mute(channel) => {
+                let muted = match channel {
+                    AudioChannel::Music => &mut settings.music_muted,
+                    AudioChannel::Sfx => &mut settings.sfx_muted,
+                    AudioChannel::Ui => &mut settings.ui_muted,
+                };
+                *muted = !*muted;
+            }
+            SettingsButtonAction::Fullscreen => settings.fullscreen = !settings.fullscreen,
+            SettingsButtonAction::Vsync => settings.vsync = !settings.vsync,
+            SettingsButtonAction::SoundAttenuation => {
+                settings.sound_attenuation = !settings.sound_attenuation
+            }
+            SettingsButtonAction::ScreenShake => settings.screen_shake = !settings.screen_shake,
+            SettingsButtonAction::Theme => settings.theme = cycle(settings.theme),
+            SettingsButtonAction::WindowScale => {
+                settings.window_scale = cycle(settings.window_scale)
+            }
+            SettingsButtonAction::Difficulty => settings.difficulty = cycle(settings.difficulty),
+            SettingsButtonAction::Controls => menu_state.set(MenuState::Controls),
+            // Return to whichever menu opened settings.
+            SettingsButtonAction::Back => match app_state.get() {
+                AppState::Paused => menu_state.set(MenuState::Pause),
+                _ => menu_state.set(MenuState::Main),
+            },
+        }
+    }
+}
+
+/// Refresh slider fills and button values after settings change.
+pub fn update_settings_text(
+    settings: Res<Settings>,
+    mut fill_query: Query<(&mut Style, &SliderFill)>,
+    mut text_query: Query<(&mut Text, &SettingsValueText)>,
+) {
+    for (mut style, fill) in &mut fill_query {
+        style.width = Val::Percent(fill.0.value(&settings) * 100.0);
+    }
+    for (mut text, value_text) in &mut text_query {
+        text.sections[0].value = value_text.0.value(&settings);
+    }
+}